@@ -0,0 +1,449 @@
+use crate::commands::{build_messages, emit_outcome, emit_token};
+use crate::fallback::ProviderUsed;
+use crate::filesystem::{self, resolve_in_project};
+use crate::generations::ActiveGenerations;
+use crate::git;
+use crate::history::{fit_request, HistoryTrim};
+use crate::models::ollama::{ChatMessage, MessageContent, Role, ToolCall, ToolDefinition};
+use crate::prompts::{resolve_system_prompt, PromptContext};
+use crate::providers::{ChatRequest, GenerationOptions, GenerationSummary, ProviderRegistry};
+use crate::search;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tokio::sync::oneshot;
+
+// Model turns per run before we assume it's going in circles
+const MAX_AGENT_STEPS: usize = 10;
+// Tool output beyond this is cut off so one big file can't flood the context
+const MAX_TOOL_OUTPUT_CHARS: usize = 16_000;
+const MAX_SEARCH_RESULTS: usize = 50;
+
+/// The workspace tools offered to the model. Paths are relative to the project root.
+pub fn workspace_tools() -> Vec<ToolDefinition> {
+    let path_only = |description: &str| {
+        json!({
+            "type": "object",
+            "properties": { "path": { "type": "string", "description": description } },
+            "required": ["path"]
+        })
+    };
+
+    vec![
+        ToolDefinition {
+            name: "read_file".to_string(),
+            description: "Read a text file from the project".to_string(),
+            parameters: path_only("File path relative to the project root"),
+        },
+        ToolDefinition {
+            name: "list_directory".to_string(),
+            description: "List the files and folders in a project directory".to_string(),
+            parameters: path_only(
+                "Directory path relative to the project root; \".\" for the root",
+            ),
+        },
+        ToolDefinition {
+            name: "search_in_files".to_string(),
+            description: "Search the project's files for a string or regex".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "is_regex": { "type": "boolean" },
+                    "case_sensitive": { "type": "boolean" }
+                },
+                "required": ["query"]
+            }),
+        },
+        ToolDefinition {
+            name: "write_file".to_string(),
+            description: "Create or overwrite a file in the project with the given content"
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "File path relative to the project root" },
+                    "content": { "type": "string" }
+                },
+                "required": ["path", "content"]
+            }),
+        },
+        ToolDefinition {
+            name: "git_diff".to_string(),
+            description: "Show uncommitted changes to a file".to_string(),
+            parameters: path_only("File path relative to the project root"),
+        },
+    ]
+}
+
+// Tools that change the workspace wait for the user to approve them
+fn needs_approval(tool: &str) -> bool {
+    tool == "write_file"
+}
+
+// Write tool calls waiting on the user, keyed by "<request_id>:<call_id>"
+#[derive(Default)]
+pub struct ToolApprovals {
+    pending: Mutex<HashMap<String, oneshot::Sender<bool>>>,
+}
+
+impl ToolApprovals {
+    fn request(&self, approval_id: &str) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(approval_id.to_string(), tx);
+        rx
+    }
+
+    /// Answers a pending approval. Returns false if nothing was waiting on it.
+    pub fn resolve(&self, approval_id: &str, approved: bool) -> bool {
+        match self.pending.lock().unwrap().remove(approval_id) {
+            Some(tx) => tx.send(approved).is_ok(),
+            None => false,
+        }
+    }
+
+    // Drops anything a cancelled or failed run left behind
+    fn forget(&self, request_id: &str) {
+        let prefix = format!("{}:", request_id);
+        self.pending
+            .lock()
+            .unwrap()
+            .retain(|id, _| !id.starts_with(&prefix));
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct AgentToolCallPayload {
+    request_id: String,
+    session_id: Option<String>,
+    call_id: String,
+    tool: String,
+    arguments: Value,
+    // Set when the frontend must answer with `approve_tool_call`
+    approval_id: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct AgentToolResultPayload {
+    request_id: String,
+    session_id: Option<String>,
+    call_id: String,
+    tool: String,
+    result: String,
+    is_error: bool,
+}
+
+fn project_path(root: &Path, relative: &str) -> Result<String, String> {
+    resolve_in_project(root, relative).map(|p| p.to_string_lossy().to_string())
+}
+
+fn string_arg<'a>(arguments: &'a Value, name: &str) -> Result<&'a str, String> {
+    arguments
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing string argument: {}", name))
+}
+
+async fn execute_tool(call: &ToolCall, root: &Path) -> Result<String, String> {
+    let args = &call.arguments;
+    match call.name.as_str() {
+        "read_file" => filesystem::read_file(project_path(root, string_arg(args, "path")?)?).await,
+        "list_directory" => {
+            let dir = project_path(root, string_arg(args, "path").unwrap_or("."))?;
+            let nodes = filesystem::list_directory(dir).await?;
+            Ok(nodes
+                .iter()
+                .map(|node| {
+                    if node.is_directory {
+                        format!("{}/", node.name)
+                    } else {
+                        node.name.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        "search_in_files" => {
+            let results = search::search_in_files(
+                root.to_string_lossy().to_string(),
+                string_arg(args, "query")?.to_string(),
+                args.get("is_regex")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+                args.get("case_sensitive")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+                Some(MAX_SEARCH_RESULTS),
+            )
+            .await?;
+            if results.is_empty() {
+                return Ok("No matches".to_string());
+            }
+            Ok(results
+                .iter()
+                .map(|r| {
+                    let file = Path::new(&r.file_path)
+                        .strip_prefix(root)
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_else(|_| r.file_path.clone());
+                    format!("{}:{}: {}", file, r.line_number, r.line_content.trim())
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        "write_file" => {
+            let path = string_arg(args, "path")?;
+            let content = string_arg(args, "content")?;
+            filesystem::write_file(project_path(root, path)?, content.to_string()).await?;
+            Ok(format!("Wrote {} bytes to {}", content.len(), path))
+        }
+        "git_diff" => {
+            let file = string_arg(args, "path")?;
+            project_path(root, file)?;
+            let diff = git::git_diff(root.to_string_lossy().to_string(), file.to_string()).await?;
+            if diff.is_empty() {
+                Ok(format!("No uncommitted changes to {}", file))
+            } else {
+                Ok(diff)
+            }
+        }
+        other => Err(format!("Unknown tool: {}", other)),
+    }
+}
+
+fn truncate_output(mut output: String) -> String {
+    if let Some((cut, _)) = output.char_indices().nth(MAX_TOOL_OUTPUT_CHARS) {
+        output.truncate(cut);
+        output.push_str("\n… (output truncated)");
+    }
+    output
+}
+
+// Announces the call, waits for approval if needed, runs it and reports the result
+async fn run_tool_call<R: Runtime>(
+    app: &AppHandle<R>,
+    request_id: &str,
+    session_id: &Option<String>,
+    call: &ToolCall,
+    root: &Path,
+) -> String {
+    let approval_id = needs_approval(&call.name).then(|| format!("{}:{}", request_id, call.id));
+    let approval = approval_id
+        .as_ref()
+        .map(|id| app.state::<ToolApprovals>().request(id));
+
+    let _ = app.emit(
+        "agent-tool-call",
+        &AgentToolCallPayload {
+            request_id: request_id.to_string(),
+            session_id: session_id.clone(),
+            call_id: call.id.clone(),
+            tool: call.name.clone(),
+            arguments: call.arguments.clone(),
+            approval_id,
+        },
+    );
+
+    println!("🔧 Tool call {}: {}", call.name, call.arguments);
+
+    let approved = match approval {
+        // A dropped sender means nobody will answer; treat it as a no
+        Some(rx) => rx.await.unwrap_or(false),
+        None => true,
+    };
+
+    let result = if approved {
+        execute_tool(call, root).await
+    } else {
+        Err(format!("The user declined the {} call", call.name))
+    };
+
+    let is_error = result.is_err();
+    let result = truncate_output(result.unwrap_or_else(|e| format!("Error: {}", e)));
+
+    let _ = app.emit(
+        "agent-tool-result",
+        &AgentToolResultPayload {
+            request_id: request_id.to_string(),
+            session_id: session_id.clone(),
+            call_id: call.id.clone(),
+            tool: call.name.clone(),
+            result: result.clone(),
+            is_error,
+        },
+    );
+
+    result
+}
+
+fn add_counts<T: std::ops::Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
+}
+
+// Token counts add up over the turns; the finish reason is the last turn's
+fn add_step(total: &mut GenerationSummary, step: GenerationSummary) {
+    total.completion_tokens = add_counts(total.completion_tokens, step.completion_tokens);
+    total.eval_duration_ms = add_counts(total.eval_duration_ms, step.eval_duration_ms);
+    total.prompt_tokens = step.prompt_tokens;
+    total.finish_reason = step.finish_reason;
+    total.tokens_per_second = None;
+}
+
+/// Runs the model in a loop with the workspace tools until it answers without
+/// calling any. Streams tokens like `generate_code`, plus `agent-tool-call` and
+/// `agent-tool-result` events for every call.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_agent<R: Runtime>(
+    app: AppHandle<R>,
+    registry: State<'_, ProviderRegistry>,
+    generations: State<'_, ActiveGenerations>,
+    model: String,
+    prompt: String,
+    provider: String,
+    project_path: String,
+    history: Vec<ChatMessage>,
+    session_id: Option<String>,
+    options: Option<GenerationOptions>,
+    prompt_context: Option<PromptContext>,
+) -> Result<String, String> {
+    let backend = registry.get(&provider)?;
+    let options = options.unwrap_or_default();
+    options.validate()?;
+
+    let root = Path::new(&project_path).to_path_buf();
+    if !root.is_dir() {
+        return Err(format!("Project folder does not exist: {}", project_path));
+    }
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(120))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut prompt_context = prompt_context.unwrap_or_default();
+    prompt_context
+        .project_path
+        .get_or_insert_with(|| project_path.clone());
+    let system_prompt = resolve_system_prompt(&prompt_context)?;
+    let messages = build_messages(system_prompt, prompt, history, Vec::new())?;
+
+    let mut request = ChatRequest {
+        model,
+        messages,
+        options,
+        tools: workspace_tools(),
+    };
+
+    println!(
+        "🤖 Running agent on {} with model: {}",
+        backend.name(),
+        request.model
+    );
+
+    let (request_id, cancelled) = generations.start();
+    let task_id = request_id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let mut first_token_at: Option<Instant> = None;
+        let mut on_token = |token: String| {
+            first_token_at.get_or_insert_with(Instant::now);
+            emit_token(&app, &task_id, &session_id, token);
+        };
+
+        let run = async {
+            let mut trim = HistoryTrim::default();
+            let mut total = GenerationSummary::default();
+
+            for _ in 0..MAX_AGENT_STEPS {
+                // Tool output piles up between steps, so each one is fitted again
+                let step_trim = fit_request(backend.as_ref(), &client, &mut request).await;
+                trim = HistoryTrim {
+                    dropped_messages: trim.dropped_messages + step_trim.dropped_messages,
+                    dropped_tokens: trim.dropped_tokens + step_trim.dropped_tokens,
+                    summarized: trim.summarized || step_trim.summarized,
+                    ..step_trim
+                };
+
+                let mut reply = String::new();
+                let mut step = backend
+                    .stream_chat(&client, &request, &mut |token: String| {
+                        reply.push_str(&token);
+                        on_token(token);
+                    })
+                    .await?;
+
+                let calls = std::mem::take(&mut step.tool_calls);
+                add_step(&mut total, step);
+
+                request.messages.push(ChatMessage {
+                    role: Role::Assistant,
+                    content: MessageContent::Text(reply),
+                    tool_calls: calls.clone(),
+                    tool_call_id: None,
+                });
+
+                if calls.is_empty() {
+                    let used = ProviderUsed {
+                        provider,
+                        model: request.model.clone(),
+                        attempts: 1,
+                    };
+                    return Ok((total, trim, used));
+                }
+
+                for call in calls {
+                    let result = run_tool_call(&app, &task_id, &session_id, &call, &root).await;
+                    request.messages.push(ChatMessage {
+                        role: Role::Tool,
+                        content: MessageContent::Text(result),
+                        tool_calls: Vec::new(),
+                        tool_call_id: Some(call.id),
+                    });
+                }
+            }
+
+            Err(format!(
+                "Agent stopped after {} steps without finishing",
+                MAX_AGENT_STEPS
+            ))
+        };
+
+        let outcome = app
+            .state::<ActiveGenerations>()
+            .run(&task_id, cancelled, run)
+            .await;
+
+        app.state::<ToolApprovals>().forget(&task_id);
+        emit_outcome(&app, task_id, session_id, outcome, first_token_at);
+    });
+
+    Ok(request_id)
+}
+
+#[tauri::command]
+pub async fn approve_tool_call(
+    approvals: State<'_, ToolApprovals>,
+    approval_id: String,
+    approved: bool,
+) -> Result<(), String> {
+    if approvals.resolve(&approval_id, approved) {
+        Ok(())
+    } else {
+        Err(format!(
+            "No tool call waiting for approval: {}",
+            approval_id
+        ))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::models::ollama::ContentPart;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Ollama and the OpenAI API both choke well before this; fail early with a clear message
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+fn mime_type_for(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Reads an image from disk as a base64 content part. Relative paths are
+/// resolved against `project_root` when one is given.
+pub fn load_image(path: &str, project_root: Option<&str>) -> Result<ContentPart, String> {
+    let mut full_path = PathBuf::from(path);
+    if full_path.is_relative() {
+        if let Some(root) = project_root {
+            full_path = Path::new(root).join(path);
+        }
+    }
+
+    let mime_type =
+        mime_type_for(&full_path).ok_or_else(|| format!("Unsupported image type: {}", path))?;
+
+    let metadata =
+        fs::metadata(&full_path).map_err(|e| format!("Failed to read image {}: {}", path, e))?;
+    if metadata.len() > MAX_IMAGE_BYTES {
+        return Err(format!(
+            "Image {} is too large ({} MB, max {} MB)",
+            path,
+            metadata.len() / (1024 * 1024),
+            MAX_IMAGE_BYTES / (1024 * 1024)
+        ));
+    }
+
+    let bytes =
+        fs::read(&full_path).map_err(|e| format!("Failed to read image {}: {}", path, e))?;
+
+    Ok(ContentPart::Image {
+        data: STANDARD.encode(bytes),
+        mime_type: Some(mime_type.to_string()),
+    })
+}
@@ -1,85 +1,134 @@
+use crate::attachments::load_image;
+use crate::config::load_provider_config;
+use crate::embeddings::{prepend_context, retrieve};
+use crate::fallback::{resolve_routes, stream_with_fallback, ProviderUsed};
+use crate::generations::ActiveGenerations;
+use crate::history::{fit_request, HistoryTrim};
 use crate::models::ollama::{
-    ChatMessage, LMStudioRequest, LMStudioResponse, OllamaChatRequest, OllamaChatResponse,
+    ChatMessage, ContentPart, MessageContent, ModelDetails, ModelInfo, OllamaPullResponse, Role,
 };
-use futures_util::StreamExt;
+use crate::prompts::{resolve_system_prompt, PromptContext};
+use crate::providers::ollama::OllamaProvider;
+use crate::providers::{ChatRequest, GenerationOptions, GenerationSummary, ProviderRegistry};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
-use std::process::Stdio;
-use std::time::Duration;
-use tauri::{AppHandle, Emitter, Runtime};
-use tokio::io::AsyncBufReadExt;
-use tokio::process::Command;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProjectData {
-    name: String,
-    code: String,
-    chat_history: String,
-}
-
-#[tauri::command]
-pub async fn check_ollama_status() -> Result<bool, String> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(2))
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    let res = client.get("http://localhost:11434").send().await;
-    Ok(res.is_ok())
+    pub(crate) name: String,
+    pub(crate) code: String,
+    pub(crate) chat_history: String,
 }
 
 #[derive(Clone, Serialize)]
 struct DownloadPayload {
+    model: String,
     percent: u64,
     status: String,
+    // Layer currently being downloaded, with its byte counts
+    digest: Option<String>,
+    completed: Option<u64>,
+    total: Option<u64>,
+    // Totals across every layer seen so far
+    completed_bytes: u64,
+    total_bytes: u64,
+}
+
+fn pull_key(model_name: &str) -> String {
+    format!("pull:{}", model_name)
 }
 
 #[tauri::command]
 pub async fn download_model<R: Runtime>(
     app: AppHandle<R>,
+    generations: State<'_, ActiveGenerations>,
     model_name: String,
 ) -> Result<(), String> {
-    let mut child = Command::new("ollama")
-        .args(&["pull", &model_name])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to spawn ollama: {}", e))?;
-
-    let stderr = child.stderr.take().ok_or("Failed to open stderr")?;
-    let mut reader = tokio::io::BufReader::new(stderr).lines();
-
-    let _handle = tauri::async_runtime::spawn(async move {
-        while let Ok(Some(line)) = reader.next_line().await {
-            let percent = if line.contains("%") {
-                line.split('%')
-                    .next()
-                    .and_then(|s| s.split_whitespace().last())
-                    .and_then(|s| s.parse::<u64>().ok())
-                    .unwrap_or(0)
-            } else {
-                0
-            };
+    // Pulls can take a long time, so only bound the connection attempt
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
 
-            let payload = DownloadPayload {
+    let ollama = OllamaProvider::new(load_provider_config().endpoint("ollama"));
+    let cancelled = generations.track(&pull_key(&model_name))?;
+
+    let mut layers: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut on_progress = |progress: OllamaPullResponse| {
+        if let (Some(digest), Some(total)) = (&progress.digest, progress.total) {
+            layers.insert(digest.clone(), (progress.completed.unwrap_or(0), total));
+        }
+        let completed_bytes: u64 = layers.values().map(|(done, _)| done).sum();
+        let total_bytes: u64 = layers.values().map(|(_, total)| total).sum();
+
+        let percent = if progress.status == "success" {
+            100
+        } else {
+            (completed_bytes * 100)
+                .checked_div(total_bytes)
+                .unwrap_or(0)
+        };
+
+        let _ = app.emit(
+            "download-progress",
+            &DownloadPayload {
+                model: model_name.clone(),
                 percent,
-                status: line.clone(),
-            };
-            let _ = app.emit("download-progress", &payload);
+                status: progress.status,
+                digest: progress.digest,
+                completed: progress.completed,
+                total: progress.total,
+                completed_bytes,
+                total_bytes,
+            },
+        );
+    };
+
+    let pull = ollama.pull_model(&client, &model_name, &mut on_progress);
+    let outcome = generations
+        .run(&pull_key(&model_name), cancelled, pull)
+        .await;
+
+    match outcome {
+        Some(res) => res,
+        None => {
+            println!("🛑 Pull of {} cancelled", model_name);
+            let _ = app.emit(
+                "download-progress",
+                &DownloadPayload {
+                    model: model_name.clone(),
+                    percent: 0,
+                    status: "cancelled".to_string(),
+                    digest: None,
+                    completed: None,
+                    total: None,
+                    completed_bytes: 0,
+                    total_bytes: 0,
+                },
+            );
+            Err(format!("Download of {} was cancelled", model_name))
         }
-    });
+    }
+}
 
-    let status = child.wait().await.map_err(|e| e.to_string())?;
-    if status.success() {
+#[tauri::command]
+pub async fn cancel_download(
+    generations: State<'_, ActiveGenerations>,
+    model_name: String,
+) -> Result<(), String> {
+    if generations.cancel(&pull_key(&model_name)) {
         Ok(())
     } else {
-        Err(format!("Ollama exited with status: {}", status))
+        Err(format!("No download in progress for: {}", model_name))
     }
 }
 
-fn get_projects_dir() -> PathBuf {
+pub(crate) fn get_projects_dir() -> PathBuf {
     dirs::document_dir()
         .unwrap_or(PathBuf::from("."))
         .join("AntigravityProjects")
@@ -120,168 +169,338 @@ pub async fn load_projects() -> Result<Vec<String>, String> {
     Ok(projects)
 }
 
+// Every generation event carries the request ID (and the caller's session ID, if any)
+// so listeners can tell concurrent generations apart.
 #[derive(Serialize)]
 struct GeneratePayload {
+    request_id: String,
+    session_id: Option<String>,
     token: String,
 }
 
-// THE CONVERSATIONAL PROTOCOL - Allow natural conversation with code generation
-const DEFAULT_SYSTEM_PROMPT: &str = "You are a helpful AI coding assistant. You can chat naturally with users and help them create code in ANY programming language (Python, JavaScript, TypeScript, Rust, Go, Java, C++, HTML/CSS, React, Vue, etc.). 
+#[derive(Serialize)]
+struct GenerateFinishedPayload {
+    request_id: String,
+    session_id: Option<String>,
+    summary: GenerationSummary,
+    history: HistoryTrim,
+    #[serde(flatten)]
+    provider: ProviderUsed,
+}
 
-IMPORTANT: When users ask you to create, build, or make something, you MUST provide the complete working code directly in markdown code blocks. Do NOT just give instructions or explanations - write the actual code.
+#[derive(Serialize)]
+struct GenerateErrorPayload {
+    request_id: String,
+    session_id: Option<String>,
+    error: String,
+}
+
+#[derive(Serialize)]
+struct GenerateCancelledPayload {
+    request_id: String,
+    session_id: Option<String>,
+}
+
+pub(crate) fn emit_token<R: Runtime>(
+    app: &AppHandle<R>,
+    request_id: &str,
+    session_id: &Option<String>,
+    token: String,
+) {
+    let _ = app.emit(
+        "generate-token",
+        &GeneratePayload {
+            request_id: request_id.to_string(),
+            session_id: session_id.clone(),
+            token,
+        },
+    );
+}
+
+/// Emits `generate-finished`, `generate-error` or `generate-cancelled` (`outcome` is
+/// `None`) once a generation has stopped.
+pub(crate) fn emit_outcome<R: Runtime>(
+    app: &AppHandle<R>,
+    task_id: String,
+    session_id: Option<String>,
+    outcome: Option<Result<(GenerationSummary, HistoryTrim, ProviderUsed), String>>,
+    first_token_at: Option<Instant>,
+) {
+    match outcome {
+        Some(Ok((summary, history, provider))) => {
+            let summary = match first_token_at {
+                Some(started) => summary.with_timing(started.elapsed()),
+                None => summary,
+            };
+            if summary.finish_reason.as_deref() == Some("length") {
+                println!("⚠️ Generation {} hit the token limit", task_id);
+            }
+
+            let _ = app.emit(
+                "generate-finished",
+                &GenerateFinishedPayload {
+                    request_id: task_id,
+                    session_id,
+                    summary,
+                    history,
+                    provider,
+                },
+            );
+        }
+        Some(Err(error)) => {
+            println!("❌ Generation {} failed: {}", task_id, error);
+            let _ = app.emit(
+                "generate-error",
+                &GenerateErrorPayload {
+                    request_id: task_id,
+                    session_id,
+                    error,
+                },
+            );
+        }
+        None => {
+            println!("🛑 Generation {} cancelled", task_id);
+            let _ = app.emit(
+                "generate-cancelled",
+                &GenerateCancelledPayload {
+                    request_id: task_id,
+                    session_id,
+                },
+            );
+        }
+    }
+}
 
-Example:
-User: 'Create a Python function to calculate fibonacci'
-You: 'Here's a Python function for fibonacci:
-```python
-def fibonacci(n):
-    if n <= 1:
-        return n
-    return fibonacci(n-1) + fibonacci(n-2)
-```'
+// Image paths may be relative to the open project
+pub(crate) fn load_images(
+    paths: Option<Vec<String>>,
+    prompt_context: &PromptContext,
+) -> Result<Vec<ContentPart>, String> {
+    paths
+        .unwrap_or_default()
+        .iter()
+        .map(|path| load_image(path, prompt_context.project_path.as_deref()))
+        .collect()
+}
 
-Be friendly and conversational, but always provide working code when requested.";
+pub(crate) fn build_messages(
+    system_prompt: String,
+    prompt: String,
+    history: Vec<ChatMessage>,
+    images: Vec<ContentPart>,
+) -> Result<Vec<ChatMessage>, String> {
+    // Build messages array - the system prompt comes from the selected preset
+    let mut messages = vec![ChatMessage::new(Role::System, system_prompt)];
+
+    for (i, message) in history.iter().enumerate() {
+        message
+            .validate()
+            .map_err(|e| format!("Invalid history message {}: {}", i, e))?;
+    }
+    messages.extend(history);
+
+    // Add user prompt, with any attached images alongside it
+    if images.is_empty() {
+        messages.push(ChatMessage::new(Role::User, prompt));
+    } else {
+        let mut parts = vec![ContentPart::Text { text: prompt }];
+        parts.extend(images);
+        messages.push(ChatMessage {
+            role: Role::User,
+            content: MessageContent::Parts(parts),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        });
+    }
+
+    Ok(messages)
+}
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_code<R: Runtime>(
     app: AppHandle<R>,
+    registry: State<'_, ProviderRegistry>,
+    generations: State<'_, ActiveGenerations>,
     model: String,
     prompt: String,
     provider: String,
-    history: String,
-) -> Result<(), String> {
+    history: Vec<ChatMessage>,
+    session_id: Option<String>,
+    options: Option<GenerationOptions>,
+    prompt_context: Option<PromptContext>,
+    images: Option<Vec<String>>,
+    context_chunks: Option<usize>,
+) -> Result<String, String> {
+    let config = load_provider_config();
+    let routes = resolve_routes(&registry, &config, &provider, &model)?;
+    let options = options.unwrap_or_default();
+    options.validate()?;
+
     let client = Client::builder()
         .timeout(Duration::from_secs(120))
         .build()
         .map_err(|e| e.to_string())?;
 
-    // Build messages array - use default system prompt for natural conversation
-    let mut messages = vec![ChatMessage {
-        role: "system".to_string(),
-        content: DEFAULT_SYSTEM_PROMPT.to_string(),
-    }];
-
-    // Parse history if provided
-    if !history.is_empty() {
-        if let Ok(history_msgs) = serde_json::from_str::<Vec<ChatMessage>>(&history) {
-            messages.extend(history_msgs);
+    let prompt_context = prompt_context.unwrap_or_default();
+    let system_prompt = resolve_system_prompt(&prompt_context)?;
+    let images = load_images(images, &prompt_context)?;
+
+    // Retrieval-augmented: prepend the project chunks closest to the prompt
+    let prompt = match context_chunks.filter(|&k| k > 0) {
+        Some(k) => {
+            let project_path = prompt_context
+                .project_path
+                .as_deref()
+                .ok_or("Retrieving project context needs prompt_context.project_path")?;
+            let chunks = retrieve(project_path, &prompt, k).await?;
+            println!("📚 Added {} project chunks to the prompt", chunks.len());
+            prepend_context(&prompt, &chunks)
         }
-    }
+        None => prompt,
+    };
+    let messages = build_messages(system_prompt, prompt, history, images)?;
 
-    // Add user prompt
-    messages.push(ChatMessage {
-        role: "user".to_string(),
-        content: prompt,
+    let request = ChatRequest {
+        model,
+        messages,
+        options,
+        tools: Vec::new(),
+    };
+
+    println!(
+        "🚀 Calling {} with model: {}",
+        routes[0].backend.name(),
+        request.model
+    );
+
+    let (request_id, cancelled) = generations.start();
+    let task_id = request_id.clone();
+
+    // Stream in the background so the frontend gets the request ID straight away
+    tauri::async_runtime::spawn(async move {
+        // Throughput is measured from the first token, so prompt processing isn't counted
+        let mut first_token_at: Option<Instant> = None;
+        let mut on_token = |token: String| {
+            first_token_at.get_or_insert_with(Instant::now);
+            emit_token(&app, &task_id, &session_id, token);
+        };
+
+        let stream = stream_with_fallback(&client, &routes, &request, &config.retry, &mut on_token);
+        let outcome = app
+            .state::<ActiveGenerations>()
+            .run(&task_id, cancelled, stream)
+            .await;
+
+        emit_outcome(&app, task_id, session_id, outcome, first_token_at);
     });
 
-    // Route to correct provider
-    match provider.as_str() {
-        "ollama" => {
-            let url = "http://localhost:11434/api/chat";
-            let request = OllamaChatRequest {
-                model: model.clone(),
-                messages: messages.clone(),
-                stream: true,
-            };
+    Ok(request_id)
+}
+
+#[tauri::command]
+pub async fn cancel_generation(
+    generations: State<'_, ActiveGenerations>,
+    request_id: String,
+) -> Result<(), String> {
+    if generations.cancel(&request_id) {
+        Ok(())
+    } else {
+        Err(format!("No active generation with ID: {}", request_id))
+    }
+}
 
-            println!("🚀 Calling Ollama with model: {}", model);
-            println!("📝 Messages: {:?}", messages);
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_text(
+    registry: State<'_, ProviderRegistry>,
+    model: String,
+    prompt: String,
+    provider: Option<String>,
+    history: Option<Vec<ChatMessage>>,
+    options: Option<GenerationOptions>,
+    prompt_context: Option<PromptContext>,
+    images: Option<Vec<String>>,
+) -> Result<String, String> {
+    let backend = registry.get(provider.as_deref().unwrap_or("ollama"))?;
+    let options = options.unwrap_or_default();
+    options.validate()?;
 
-            let response = client
-                .post(url)
-                .json(&request)
-                .send()
-                .await
-                .map_err(|e| format!("Failed to connect to Ollama: {}", e))?;
+    let client = Client::builder()
+        .timeout(Duration::from_secs(120))
+        .build()
+        .map_err(|e| e.to_string())?;
 
-            if !response.status().is_success() {
-                return Err(format!("Ollama API Error: {}", response.status()));
-            }
+    // "default" means whatever the backend has available first
+    let model = if model.is_empty() || model == "default" {
+        backend
+            .list_models(&client)
+            .await?
+            .into_iter()
+            .next()
+            .map(|m| m.name)
+            .ok_or_else(|| format!("No models available from {}", backend.name()))?
+    } else {
+        model
+    };
+
+    let prompt_context = prompt_context.unwrap_or_default();
+    let system_prompt = resolve_system_prompt(&prompt_context)?;
+    let images = load_images(images, &prompt_context)?;
+    let messages = build_messages(system_prompt, prompt, history.unwrap_or_default(), images)?;
+    let mut request = ChatRequest {
+        model,
+        messages,
+        options,
+        tools: Vec::new(),
+    };
+    fit_request(backend.as_ref(), &client, &mut request).await;
 
-            println!("✅ Ollama responded, streaming...");
+    println!(
+        "🚀 Calling {} with model: {} (non-streaming)",
+        backend.name(),
+        request.model
+    );
 
-            let mut stream = response.bytes_stream();
+    backend.chat(&client, &request).await
+}
 
-            while let Some(item) = stream.next().await {
-                let chunk = item.map_err(|e| e.to_string())?;
-                let text = String::from_utf8_lossy(&chunk);
+#[tauri::command]
+pub async fn list_models(
+    registry: State<'_, ProviderRegistry>,
+    provider: String,
+) -> Result<Vec<ModelInfo>, String> {
+    let backend = registry.get(&provider)?;
 
-                for line in text.split('\n') {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
 
-                    if let Ok(res) = serde_json::from_str::<OllamaChatResponse>(line) {
-                        if let Some(msg) = res.message {
-                            println!("📨 Token: {}", msg.content);
-                            let _ =
-                                app.emit("generate-token", &GeneratePayload { token: msg.content });
-                        }
+    backend.list_models(&client).await
+}
 
-                        if res.done {
-                            println!("✅ Generation finished");
-                            let _ = app.emit("generate-finished", ());
-                        }
-                    }
-                }
-            }
-        }
-        "lmstudio" => {
-            let url = "http://localhost:1234/v1/chat/completions";
-            let request = LMStudioRequest {
-                model: model.clone(),
-                messages,
-                stream: true,
-                temperature: 0.7,
-            };
+#[tauri::command]
+pub async fn show_model(model: String) -> Result<ModelDetails, String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
 
-            let response = client
-                .post(url)
-                .json(&request)
-                .send()
-                .await
-                .map_err(|e| format!("Failed to connect to LM Studio: {}", e))?;
+    let ollama = OllamaProvider::new(load_provider_config().endpoint("ollama"));
+    ollama.show_model(&client, &model).await
+}
 
-            if !response.status().is_success() {
-                return Err(format!("LM Studio API Error: {}", response.status()));
-            }
+#[tauri::command]
+pub async fn delete_model(model: String) -> Result<(), String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| e.to_string())?;
 
-            let mut stream = response.bytes_stream();
-
-            while let Some(item) = stream.next().await {
-                let chunk = item.map_err(|e| e.to_string())?;
-                let text = String::from_utf8_lossy(&chunk);
-
-                for line in text.split('\n') {
-                    if line.trim().is_empty() || !line.starts_with("data: ") {
-                        continue;
-                    }
-
-                    let json_str = line.trim_start_matches("data: ");
-                    if json_str == "[DONE]" {
-                        let _ = app.emit("generate-finished", ());
-                        break;
-                    }
-
-                    if let Ok(res) = serde_json::from_str::<LMStudioResponse>(json_str) {
-                        if let Some(choice) = res.choices.first() {
-                            if let Some(delta) = &choice.delta {
-                                if !delta.content.is_empty() {
-                                    let _ = app.emit(
-                                        "generate-token",
-                                        &GeneratePayload {
-                                            token: delta.content.clone(),
-                                        },
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        _ => return Err(format!("Unknown provider: {}", provider)),
-    }
+    let ollama = OllamaProvider::new(load_provider_config().endpoint("ollama"));
+    ollama.delete_model(&client, &model).await?;
 
+    println!("🗑️  Deleted model: {}", model);
     Ok(())
 }
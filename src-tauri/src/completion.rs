@@ -0,0 +1,224 @@
+use crate::config::load_provider_config;
+use crate::models::ollama::{OllamaGenerateRequest, OllamaOptions};
+use crate::providers::ollama::OllamaProvider;
+use reqwest::Client;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::State;
+use tokio::sync::oneshot;
+
+const DEFAULT_COMPLETION_MODEL: &str = "qwen2.5-coder:1.5b";
+// Past this a suggestion shows up after the user has moved on
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_MAX_TOKENS: u32 = 64;
+// Small completion models have small windows, and prompt processing dominates latency
+const MAX_PREFIX_CHARS: usize = 6000;
+const MAX_SUFFIX_CHARS: usize = 2000;
+
+// Raw FIM templates for model families we know; anything else goes through
+// Ollama's `suffix` field and the model's own insert template.
+const FIM_TEMPLATES: &[(&str, &str, &str, &str)] = &[
+    // (model name contains, before prefix, before suffix, before middle)
+    (
+        "qwen2.5-coder",
+        "<|fim_prefix|>",
+        "<|fim_suffix|>",
+        "<|fim_middle|>",
+    ),
+    (
+        "codegemma",
+        "<|fim_prefix|>",
+        "<|fim_suffix|>",
+        "<|fim_middle|>",
+    ),
+    ("starcoder", "<fim_prefix>", "<fim_suffix>", "<fim_middle>"),
+    ("codellama", "<PRE> ", " <SUF>", " <MID>"),
+    (
+        "deepseek-coder",
+        "<｜fim▁begin｜>",
+        "<｜fim▁hole｜>",
+        "<｜fim▁end｜>",
+    ),
+];
+
+// End-of-insertion markers some models print instead of stopping
+const FIM_STOP_TOKENS: &[&str] = &[
+    "<|endoftext|>",
+    "<|file_separator|>",
+    "<|fim_pad|>",
+    "<EOT>",
+    "<｜end▁of▁sentence｜>",
+];
+
+// Only the newest inline completion matters; starting one cancels the last
+#[derive(Default)]
+pub struct InlineCompletions {
+    latest: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl InlineCompletions {
+    fn supersede(&self) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
+        if let Some(previous) = self.latest.lock().unwrap().replace(tx) {
+            let _ = previous.send(());
+        }
+        rx
+    }
+}
+
+fn tail_chars(text: &str, max_chars: usize) -> &str {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text;
+    }
+    let start = text
+        .char_indices()
+        .nth(count - max_chars)
+        .map_or(0, |(i, _)| i);
+    // Start on a line boundary rather than mid-line
+    match text[start..].find('\n') {
+        Some(newline) => &text[start + newline + 1..],
+        None => &text[start..],
+    }
+}
+
+fn head_chars(text: &str, max_chars: usize) -> &str {
+    let end = text
+        .char_indices()
+        .nth(max_chars)
+        .map_or(text.len(), |(i, _)| i);
+    match text[..end].rfind('\n') {
+        Some(newline) if end < text.len() => &text[..newline + 1],
+        _ => &text[..end],
+    }
+}
+
+fn line_comment(language: &str) -> Option<&'static str> {
+    match language.to_ascii_lowercase().as_str() {
+        "rust" | "javascript" | "typescript" | "javascriptreact" | "typescriptreact" | "java"
+        | "c" | "cpp" | "csharp" | "go" | "kotlin" | "swift" | "scala" | "dart" | "php" => {
+            Some("//")
+        }
+        "python" | "ruby" | "shell" | "bash" | "yaml" | "toml" | "r" | "perl" | "dockerfile" => {
+            Some("#")
+        }
+        "sql" | "lua" | "haskell" => Some("--"),
+        _ => None,
+    }
+}
+
+// True if `rest` is just the start of `suffix` again, ignoring indentation and blank lines
+fn repeats_suffix(rest: &str, suffix: &str) -> bool {
+    let rest: Vec<&str> = rest
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let next: Vec<&str> = suffix
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .take(rest.len())
+        .collect();
+    !rest.is_empty() && rest == next
+}
+
+// Strips stop markers and anything the model wrote past the cursor's suffix
+fn clean_insertion(mut text: String, suffix: &str) -> String {
+    for token in FIM_STOP_TOKENS {
+        if let Some(index) = text.find(token) {
+            text.truncate(index);
+        }
+    }
+
+    // Models often run on into the code that already follows the cursor
+    let line_starts = std::iter::once(0).chain(text.match_indices('\n').map(|(i, _)| i + 1));
+    let overlap = line_starts
+        .filter(|&start| start < text.len())
+        .find(|&start| repeats_suffix(&text[start..], suffix))
+        .or_else(|| {
+            let next_line = suffix.lines().map(str::trim).find(|l| l.len() > 3)?;
+            let index = text.find(next_line)?;
+            let line_start = text[..index].rfind('\n').map_or(0, |i| i + 1);
+            text[line_start..index]
+                .trim()
+                .is_empty()
+                .then_some(line_start)
+        });
+    if let Some(start) = overlap {
+        text.truncate(start);
+    }
+
+    text.trim_end().to_string()
+}
+
+/// Completes the code between `prefix` and `suffix` with a fill-in-the-middle
+/// model and returns only the text to insert at the cursor. A newer call
+/// cancels this one, which then fails with "Completion superseded".
+#[tauri::command]
+pub async fn complete_inline(
+    completions: State<'_, InlineCompletions>,
+    prefix: String,
+    suffix: String,
+    path: Option<String>,
+    language: Option<String>,
+    model: Option<String>,
+    max_tokens: Option<u32>,
+) -> Result<String, String> {
+    let superseded = completions.supersede();
+    let model = model.unwrap_or_else(|| DEFAULT_COMPLETION_MODEL.to_string());
+
+    let client = Client::new();
+    let mut prefix = tail_chars(&prefix, MAX_PREFIX_CHARS).to_string();
+    let suffix = head_chars(&suffix, MAX_SUFFIX_CHARS);
+
+    // A "Path:" header comment is how most code models were trained to see file names
+    if let (Some(path), Some(comment)) = (&path, language.as_deref().and_then(line_comment)) {
+        prefix = format!("{} Path: {}\n{}", comment, path, prefix);
+    }
+
+    let template = FIM_TEMPLATES
+        .iter()
+        .find(|(family, ..)| model.to_lowercase().contains(family));
+    let (prompt, suffix_field, raw) = match template {
+        Some((_, pre, suf, mid)) => (
+            format!("{}{}{}{}{}", pre, prefix, suf, suffix, mid),
+            None,
+            true,
+        ),
+        None => (prefix, Some(suffix.to_string()), false),
+    };
+
+    let request = OllamaGenerateRequest {
+        model,
+        prompt,
+        suffix: suffix_field,
+        raw,
+        stream: false,
+        options: OllamaOptions {
+            temperature: Some(0.2),
+            num_predict: Some(max_tokens.unwrap_or(DEFAULT_MAX_TOKENS)),
+            stop: Some(vec!["\n\n\n".to_string()]),
+            ..Default::default()
+        },
+    };
+
+    let ollama = OllamaProvider::new(load_provider_config().endpoint("ollama"));
+    let started = Instant::now();
+    let generate = tokio::time::timeout(COMPLETION_TIMEOUT, ollama.generate(&client, &request));
+    let text = tokio::select! {
+        res = generate => res.map_err(|_| {
+            format!("Completion timed out after {}s", COMPLETION_TIMEOUT.as_secs())
+        })??,
+        _ = superseded => return Err("Completion superseded".to_string()),
+    };
+
+    let insertion = clean_insertion(text, suffix);
+    println!(
+        "⚡ Inline completion from {} in {}ms ({} chars)",
+        request.model,
+        started.elapsed().as_millis(),
+        insertion.len()
+    );
+    Ok(insertion)
+}
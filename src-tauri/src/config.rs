@@ -0,0 +1,274 @@
+use crate::providers::ProviderRegistry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProviderEndpoint {
+    pub base_url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl ProviderEndpoint {
+    pub(crate) fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            headers: HashMap::new(),
+        }
+    }
+}
+
+// How often to retry a provider that couldn't be reached or answered with a 5xx
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32, // per provider, including the first try
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 8000,
+        }
+    }
+}
+
+// A provider to fall back to and the model to use there. Entries saved without
+// a model by older versions are skipped.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FallbackProvider {
+    pub provider: String,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+// Per-provider endpoints, keyed by registry ID ("ollama", "lmstudio", ...)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProviderConfig {
+    #[serde(default)]
+    pub endpoints: HashMap<String, ProviderEndpoint>,
+    // Context window overrides in tokens, keyed by model name
+    #[serde(default)]
+    pub context_lengths: HashMap<String, u32>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    // Tried in order when the requested provider stays unreachable
+    #[serde(default)]
+    pub fallbacks: Vec<FallbackProvider>,
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        let mut openrouter = ProviderEndpoint::new("https://openrouter.ai/api/v1");
+        openrouter.headers.insert(
+            "HTTP-Referer".to_string(),
+            "https://vibe-studio.app".to_string(),
+        );
+        openrouter
+            .headers
+            .insert("X-Title".to_string(), "Vibe Studio".to_string());
+
+        let endpoints = HashMap::from([
+            (
+                "ollama".to_string(),
+                ProviderEndpoint::new("http://localhost:11434"),
+            ),
+            (
+                "lmstudio".to_string(),
+                ProviderEndpoint::new("http://localhost:1234/v1"),
+            ),
+            (
+                "openai".to_string(),
+                ProviderEndpoint::new("https://api.openai.com/v1"),
+            ),
+            ("openrouter".to_string(), openrouter),
+            (
+                "anthropic".to_string(),
+                ProviderEndpoint::new("https://api.anthropic.com"),
+            ),
+            (
+                "gemini".to_string(),
+                ProviderEndpoint::new("https://generativelanguage.googleapis.com"),
+            ),
+        ]);
+
+        Self {
+            endpoints,
+            context_lengths: HashMap::new(),
+            retry: RetryPolicy::default(),
+            fallbacks: Vec::new(),
+        }
+    }
+}
+
+impl ProviderConfig {
+    /// Configured endpoint for `provider`, falling back to the built-in default.
+    pub fn endpoint(&self, provider: &str) -> ProviderEndpoint {
+        if let Some(endpoint) = self.endpoints.get(provider) {
+            return endpoint.clone();
+        }
+
+        Self::default()
+            .endpoints
+            .remove(provider)
+            .unwrap_or_else(|| ProviderEndpoint::new(""))
+    }
+}
+
+fn get_config_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or(PathBuf::from("."))
+        .join("vibe-studio")
+        .join("providers.json")
+}
+
+/// Reads the saved configuration; a missing file means defaults, but a file that
+/// doesn't parse is an error, so it never gets saved over.
+fn read_provider_config() -> Result<ProviderConfig, String> {
+    let path = get_config_path();
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ProviderConfig::default()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    serde_json::from_str(&json).map_err(|e| {
+        format!(
+            "Invalid provider config at {} (fix or remove it to save changes): {}",
+            path.display(),
+            e
+        )
+    })
+}
+
+// For callers that only read: an unreadable file falls back to the defaults
+pub fn load_provider_config() -> ProviderConfig {
+    read_provider_config().unwrap_or_else(|e| {
+        println!("⚠️ {}", e);
+        ProviderConfig::default()
+    })
+}
+
+fn save_provider_config(config: &ProviderConfig) -> Result<(), String> {
+    let path = get_config_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_provider_config() -> Result<ProviderConfig, String> {
+    let mut config = read_provider_config()?;
+
+    // Show defaults for anything the user hasn't overridden
+    for (id, endpoint) in ProviderConfig::default().endpoints {
+        config.endpoints.entry(id).or_insert(endpoint);
+    }
+
+    Ok(config)
+}
+
+#[tauri::command]
+pub async fn set_provider_endpoint(
+    registry: State<'_, ProviderRegistry>,
+    provider: String,
+    base_url: String,
+    headers: Option<HashMap<String, String>>,
+) -> Result<(), String> {
+    let base_url = base_url.trim().trim_end_matches('/').to_string();
+    if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+        return Err(format!("Invalid base URL: {}", base_url));
+    }
+    registry.get(&provider)?;
+
+    let mut config = read_provider_config()?;
+    config.endpoints.insert(
+        provider,
+        ProviderEndpoint {
+            base_url,
+            headers: headers.unwrap_or_default(),
+        },
+    );
+    save_provider_config(&config)?;
+
+    registry.reload(&config);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reset_provider_endpoint(
+    registry: State<'_, ProviderRegistry>,
+    provider: String,
+) -> Result<(), String> {
+    let mut config = read_provider_config()?;
+    config.endpoints.remove(&provider);
+    save_provider_config(&config)?;
+
+    registry.reload(&config);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_model_context_length(
+    model: String,
+    context_length: Option<u32>,
+) -> Result<(), String> {
+    let mut config = read_provider_config()?;
+    match context_length {
+        Some(0) => return Err("Context length must be at least 1".to_string()),
+        Some(tokens) => config.context_lengths.insert(model, tokens),
+        None => config.context_lengths.remove(&model),
+    };
+    save_provider_config(&config)
+}
+
+#[tauri::command]
+pub async fn set_retry_policy(
+    max_attempts: u32,
+    initial_backoff_ms: u64,
+    max_backoff_ms: u64,
+) -> Result<(), String> {
+    if max_attempts == 0 {
+        return Err("Max attempts must be at least 1".to_string());
+    }
+    if max_backoff_ms < initial_backoff_ms {
+        return Err("Max backoff must not be shorter than the initial backoff".to_string());
+    }
+
+    let mut config = read_provider_config()?;
+    config.retry = RetryPolicy {
+        max_attempts,
+        initial_backoff_ms,
+        max_backoff_ms,
+    };
+    save_provider_config(&config)
+}
+
+#[tauri::command]
+pub async fn set_fallback_providers(
+    registry: State<'_, ProviderRegistry>,
+    fallbacks: Vec<FallbackProvider>,
+) -> Result<(), String> {
+    for fallback in &fallbacks {
+        let backend = registry.get(&fallback.provider)?;
+        // Fallbacks apply whatever provider was picked, so the requested
+        // model name can't be carried over to another backend
+        if fallback.model.as_deref().unwrap_or("").trim().is_empty() {
+            return Err(format!(
+                "Choose a model for the {} fallback; model names differ between providers",
+                backend.name()
+            ));
+        }
+    }
+
+    let mut config = read_provider_config()?;
+    config.fallbacks = fallbacks;
+    save_provider_config(&config)
+}
@@ -0,0 +1,394 @@
+use crate::filesystem::{self, resolve_in_project};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// Search/replace block markers, as described in the "edit" system prompt preset
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+// Unchanged lines shown around each hunk of a preview diff
+const DIFF_CONTEXT: usize = 3;
+// Beyond this many LCS cells the diff just shows the changed region replaced wholesale
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EditBlock {
+    pub path: String,
+    pub search: String,
+    pub replace: String,
+}
+
+// One file touched by a set of edits, with a unified diff of the change
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileEdit {
+    pub path: String,
+    pub diff: String,
+    pub created: bool,
+    pub blocks: usize,
+}
+
+fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+// "`src/main.rs`:" or "**src/main.rs**" -> "src/main.rs"
+fn clean_path(line: &str) -> String {
+    line.trim()
+        .trim_end_matches(':')
+        .trim_matches(|c| c == '`' || c == '*' || c == '#' || c == ' ')
+        .to_string()
+}
+
+/// Pulls every search/replace block out of a model response. The file path is the
+/// last non-fence line before `<<<<<<< SEARCH`; consecutive blocks may omit it to
+/// keep editing the same file.
+pub fn parse_edit_blocks(response: &str) -> Result<Vec<EditBlock>, String> {
+    let lines: Vec<&str> = response.lines().collect();
+    let mut blocks: Vec<EditBlock> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        if lines[i].trim_end() != SEARCH_MARKER {
+            i += 1;
+            continue;
+        }
+        let number = blocks.len() + 1;
+
+        let previous = lines[..i]
+            .iter()
+            .rev()
+            .find(|l| !l.trim().is_empty() && !is_fence(l));
+        let path = match previous {
+            Some(line) if line.trim_end() == REPLACE_MARKER => {
+                blocks.last().map(|b| b.path.clone())
+            }
+            Some(line) => Some(clean_path(line)).filter(|p| !p.is_empty()),
+            None => None,
+        }
+        .ok_or_else(|| format!("Edit block {} has no file path before it", number))?;
+
+        let mut search = Vec::new();
+        i += 1;
+        while i < lines.len() && lines[i].trim_end() != DIVIDER {
+            search.push(lines[i]);
+            i += 1;
+        }
+        if i == lines.len() {
+            return Err(format!(
+                "Edit block {} for {} is missing its {} divider",
+                number, path, DIVIDER
+            ));
+        }
+
+        let mut replace = Vec::new();
+        i += 1;
+        while i < lines.len() && lines[i].trim_end() != REPLACE_MARKER {
+            replace.push(lines[i]);
+            i += 1;
+        }
+        if i == lines.len() {
+            return Err(format!(
+                "Edit block {} for {} is missing its {} marker",
+                number, path, REPLACE_MARKER
+            ));
+        }
+
+        blocks.push(EditBlock {
+            path,
+            search: search.join("\n"),
+            replace: replace.join("\n"),
+        });
+        i += 1;
+    }
+
+    Ok(blocks)
+}
+
+fn split_lines(text: &str) -> Vec<&str> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        text.lines().collect()
+    }
+}
+
+// Start indexes of every place `needle` occurs as a run of whole lines in `haystack`
+fn find_line_matches(
+    haystack: &[&str],
+    needle: &[&str],
+    eq: impl Fn(&str, &str) -> bool,
+) -> Vec<usize> {
+    if needle.len() > haystack.len() {
+        return Vec::new();
+    }
+    (0..=haystack.len() - needle.len())
+        .filter(|&start| needle.iter().zip(&haystack[start..]).all(|(a, b)| eq(a, b)))
+        .collect()
+}
+
+/// Applies one block to `content`. An exact match is preferred; failing that,
+/// trailing whitespace is ignored, since models often drop it.
+fn apply_block(content: &str, block: &EditBlock, number: usize) -> Result<String, String> {
+    let file_lines = split_lines(content);
+    let search_lines = split_lines(&block.search);
+
+    let mut matches = find_line_matches(&file_lines, &search_lines, |a, b| a == b);
+    if matches.is_empty() {
+        matches = find_line_matches(&file_lines, &search_lines, |a, b| {
+            a.trim_end() == b.trim_end()
+        });
+    }
+
+    let start = match matches.as_slice() {
+        [start] => *start,
+        [] => {
+            return Err(format!(
+                "SEARCH block {} doesn't match the current contents of {} (looking for: {:?})",
+                number,
+                block.path,
+                search_lines.first().unwrap_or(&"")
+            ))
+        }
+        _ => {
+            return Err(format!(
+            "SEARCH block {} matches {} places in {}; it needs more surrounding lines to be unique",
+            number,
+            matches.len(),
+            block.path
+        ))
+        }
+    };
+
+    let mut new_lines: Vec<&str> = file_lines[..start].to_vec();
+    new_lines.extend(split_lines(&block.replace));
+    new_lines.extend(&file_lines[start + search_lines.len()..]);
+
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut updated = new_lines.join(newline);
+    if content.ends_with('\n') && !updated.is_empty() {
+        updated.push_str(newline);
+    }
+    Ok(updated)
+}
+
+enum DiffOp<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffOp<'a>> {
+    // Edits are usually local, so only the middle needs a real LCS
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<DiffOp> = old[..prefix].iter().map(|l| DiffOp::Equal(l)).collect();
+
+    let (n, m) = (old_mid.len(), new_mid.len());
+    if n * m <= MAX_DIFF_CELLS {
+        let width = m + 1;
+        let mut lcs = vec![0u32; (n + 1) * width];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i * width + j] = if old_mid[i] == new_mid[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if old_mid[i] == new_mid[j] {
+                ops.push(DiffOp::Equal(old_mid[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                ops.push(DiffOp::Delete(old_mid[i]));
+                i += 1;
+            } else {
+                ops.push(DiffOp::Insert(new_mid[j]));
+                j += 1;
+            }
+        }
+        ops.extend(old_mid[i..].iter().map(|l| DiffOp::Delete(l)));
+        ops.extend(new_mid[j..].iter().map(|l| DiffOp::Insert(l)));
+    } else {
+        ops.extend(old_mid.iter().map(|l| DiffOp::Delete(l)));
+        ops.extend(new_mid.iter().map(|l| DiffOp::Insert(l)));
+    }
+
+    ops.extend(old[old.len() - suffix..].iter().map(|l| DiffOp::Equal(l)));
+    ops
+}
+
+/// Unified diff between two versions of `path`, in the same shape `git diff` prints.
+pub fn unified_diff(path: &str, old: &str, new: &str, created: bool) -> String {
+    let old_lines = split_lines(old);
+    let new_lines = split_lines(new);
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    let mut out = if created {
+        format!("--- /dev/null\n+++ b/{}\n", path)
+    } else {
+        format!("--- a/{}\n+++ b/{}\n", path, path)
+    };
+
+    // Line numbers (0-based) in the old and new file before each op
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for op in &ops {
+        positions.push((old_pos, new_pos));
+        match op {
+            DiffOp::Equal(_) => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            DiffOp::Delete(_) => old_pos += 1,
+            DiffOp::Insert(_) => new_pos += 1,
+        }
+    }
+    positions.push((old_pos, new_pos));
+
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Equal(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut c = 0;
+    while c < changes.len() {
+        // Changes separated by no more than two contexts' worth of lines share a hunk
+        let first = changes[c];
+        let mut last = first;
+        while c + 1 < changes.len() && changes[c + 1] - last <= 2 * DIFF_CONTEXT + 1 {
+            c += 1;
+            last = changes[c];
+        }
+        c += 1;
+
+        let start = first.saturating_sub(DIFF_CONTEXT);
+        let end = (last + DIFF_CONTEXT + 1).min(ops.len());
+        let (old_start, new_start) = positions[start];
+        let (old_end, new_end) = positions[end];
+        let (old_count, new_count) = (old_end - old_start, new_end - new_start);
+
+        // An empty range is numbered by the line before it, as in `diff -u`
+        let line_number = |start: usize, count: usize| if count == 0 { start } else { start + 1 };
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            line_number(old_start, old_count),
+            old_count,
+            line_number(new_start, new_count),
+            new_count
+        ));
+
+        for op in &ops[start..end] {
+            let (prefix, line) = match op {
+                DiffOp::Equal(line) => (' ', line),
+                DiffOp::Delete(line) => ('-', line),
+                DiffOp::Insert(line) => ('+', line),
+            };
+            out.push(prefix);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Parses the edit blocks in `response` and applies them to files under
+/// `project_path`. Every block is checked before anything is written, so a
+/// mismatch leaves the project untouched. With `dry_run`, only the diffs are returned.
+#[tauri::command]
+pub async fn apply_edits(
+    project_path: String,
+    response: String,
+    dry_run: Option<bool>,
+) -> Result<Vec<FileEdit>, String> {
+    let blocks = parse_edit_blocks(&response)?;
+    if blocks.is_empty() {
+        return Err("No edit blocks found in the response".to_string());
+    }
+
+    let root = Path::new(&project_path);
+
+    // Group blocks by file, keeping the order files first appear in
+    let mut files: Vec<(String, Vec<(usize, &EditBlock)>)> = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        match files.iter_mut().find(|(path, _)| *path == block.path) {
+            Some((_, file_blocks)) => file_blocks.push((i + 1, block)),
+            None => files.push((block.path.clone(), vec![(i + 1, block)])),
+        }
+    }
+
+    let mut planned = Vec::new();
+    for (path, file_blocks) in files {
+        let full_path = resolve_in_project(root, &path)?;
+        let original = match fs::read_to_string(&full_path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(format!("Failed to read {}: {}", path, e)),
+        };
+
+        let mut content = original.clone().unwrap_or_default();
+        for (number, block) in &file_blocks {
+            content = if block.search.trim().is_empty() {
+                // An empty SEARCH section creates the file
+                if !content.is_empty() {
+                    return Err(format!(
+                        "SEARCH block {} is empty but {} already has content",
+                        number, path
+                    ));
+                }
+                format!("{}\n", block.replace)
+            } else if original.is_none() && content.is_empty() {
+                return Err(format!(
+                    "SEARCH block {} targets {}, which doesn't exist",
+                    number, path
+                ));
+            } else {
+                apply_block(&content, block, *number)?
+            };
+        }
+
+        let created = original.is_none();
+        let diff = unified_diff(&path, original.as_deref().unwrap_or(""), &content, created);
+        planned.push((
+            full_path,
+            content,
+            FileEdit {
+                path,
+                diff,
+                created,
+                blocks: file_blocks.len(),
+            },
+        ));
+    }
+
+    let mut edits = Vec::new();
+    for (full_path, content, edit) in planned {
+        if !dry_run.unwrap_or(false) {
+            filesystem::write_file(full_path.to_string_lossy().to_string(), content).await?;
+        }
+        edits.push(edit);
+    }
+
+    Ok(edits)
+}
@@ -0,0 +1,403 @@
+use crate::config::load_provider_config;
+use crate::providers::ollama::OllamaProvider;
+use crate::search::{is_binary_path, is_project_entry};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Runtime};
+use walkdir::WalkDir;
+
+const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
+// Bump when the chunking or file format changes so old indexes get rebuilt
+const INDEX_VERSION: u32 = 2;
+const CHUNK_LINES: usize = 40;
+const CHUNK_OVERLAP: usize = 8;
+// Keeps minified files from blowing past the embedding model's context
+const MAX_CHUNK_CHARS: usize = 4000;
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+const EMBED_BATCH_SIZE: usize = 16;
+const DEFAULT_TOP_K: usize = 5;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EmbeddingIndex {
+    version: u32,
+    model: String,
+    files: HashMap<String, IndexedFile>, // keyed by path relative to the project
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexedFile {
+    modified: u64,
+    size: u64,
+    chunks: Vec<IndexedChunk>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexedChunk {
+    start_line: usize,
+    end_line: usize,
+    content: String,
+    vector: Vec<f32>, // unit length, so cosine similarity is a dot product
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexStats {
+    pub model: String,
+    pub files_indexed: usize,
+    pub files_unchanged: usize,
+    pub files_removed: usize,
+    pub chunks: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RetrievedChunk {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+    pub score: f32,
+}
+
+#[derive(Clone, Serialize)]
+struct IndexProgressPayload {
+    project_path: String,
+    embedded: usize,
+    total: usize,
+}
+
+fn index_path(project_path: &str) -> PathBuf {
+    Path::new(project_path)
+        .join(".vibe-studio")
+        .join("embeddings.json")
+}
+
+fn load_index(project_path: &str) -> Option<EmbeddingIndex> {
+    let content = fs::read_to_string(index_path(project_path)).ok()?;
+    serde_json::from_str::<EmbeddingIndex>(&content)
+        .ok()
+        .filter(|index| index.version == INDEX_VERSION)
+}
+
+fn save_index(project_path: &str, index: &EmbeddingIndex) -> Result<(), String> {
+    let path = index_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string(index).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to save embeddings index: {}", e))
+}
+
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+// Overlapping windows of lines, so code near a boundary shows up in both chunks
+fn chunk_lines(content: &str) -> Vec<(usize, usize, String)> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < lines.len() {
+        let window_end = (start + CHUNK_LINES).min(lines.len());
+        let text = lines[start..window_end].join("\n");
+        let truncated = truncate_chars(&text, MAX_CHUNK_CHARS);
+        // A cut-down chunk ends on the last line it still (partly) holds, so
+        // citations match its content; the next chunk picks up after it
+        let end = if truncated.len() < text.len() {
+            start + truncated.lines().count()
+        } else {
+            window_end
+        };
+        if !truncated.trim().is_empty() {
+            chunks.push((start + 1, end, truncated.to_string()));
+        }
+        if end == lines.len() {
+            break;
+        }
+        start = if end < window_end {
+            end
+        } else {
+            end - CHUNK_OVERLAP
+        };
+    }
+
+    chunks
+}
+
+async fn embed_texts(
+    ollama: &OllamaProvider,
+    client: &Client,
+    model: &str,
+    texts: Vec<String>,
+) -> Result<Vec<Vec<f32>>, String> {
+    let vectors = ollama.embed(client, model, texts).await?;
+    Ok(vectors.into_iter().map(normalize).collect())
+}
+
+fn embedding_client() -> Result<Client, String> {
+    Client::builder()
+        .timeout(Duration::from_secs(120))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Chunks the project's text files and embeds them with Ollama into
+/// `.vibe-studio/embeddings.json`. Files unchanged since the last run keep
+/// their vectors; switching models rebuilds the whole index.
+#[tauri::command]
+pub async fn index_project<R: Runtime>(
+    app: AppHandle<R>,
+    project_path: String,
+    model: Option<String>,
+) -> Result<IndexStats, String> {
+    let model = model.unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string());
+    let client = embedding_client()?;
+    let ollama = OllamaProvider::new(load_provider_config().endpoint("ollama"));
+
+    let mut previous = load_index(&project_path)
+        .filter(|index| index.model == model)
+        .map(|index| index.files)
+        .unwrap_or_default();
+
+    let root = Path::new(&project_path);
+    let mut files = HashMap::new();
+    let mut pending: Vec<(String, IndexedFile)> = Vec::new();
+    let mut pending_chunks = 0;
+
+    for entry in WalkDir::new(root)
+        .follow_links(false)
+        .max_depth(20)
+        .into_iter()
+        .filter_entry(is_project_entry)
+        .flatten()
+    {
+        let path = entry.path();
+        if !entry.file_type().is_file() || is_binary_path(path) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.len() > MAX_FILE_BYTES {
+            continue;
+        }
+
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        if let Some(existing) = previous.remove(&relative) {
+            if existing.modified == modified && existing.size == metadata.len() {
+                files.insert(relative, existing);
+                continue;
+            }
+        }
+
+        let Ok(content) = fs::read_to_string(path) else {
+            continue; // Not UTF-8 text
+        };
+        let chunks: Vec<IndexedChunk> = chunk_lines(&content)
+            .into_iter()
+            .map(|(start_line, end_line, content)| IndexedChunk {
+                start_line,
+                end_line,
+                content,
+                vector: Vec::new(),
+            })
+            .collect();
+        pending_chunks += chunks.len();
+        pending.push((
+            relative,
+            IndexedFile {
+                modified,
+                size: metadata.len(),
+                chunks,
+            },
+        ));
+    }
+
+    let files_unchanged = files.len();
+    let files_indexed = pending.len();
+    let files_removed = previous.len();
+    println!(
+        "📚 Indexing {}: {} changed files ({} chunks), {} unchanged",
+        project_path, files_indexed, pending_chunks, files_unchanged
+    );
+
+    let mut embedded = 0;
+    for (relative, mut file) in pending {
+        for batch in file.chunks.chunks_mut(EMBED_BATCH_SIZE) {
+            // The path gives the model something to go on for short or generic chunks
+            let texts = batch
+                .iter()
+                .map(|chunk| format!("{}\n{}", relative, chunk.content))
+                .collect();
+            let vectors = embed_texts(&ollama, &client, &model, texts).await?;
+            for (chunk, vector) in batch.iter_mut().zip(vectors) {
+                chunk.vector = vector;
+            }
+
+            embedded += batch.len();
+            let _ = app.emit(
+                "index-progress",
+                IndexProgressPayload {
+                    project_path: project_path.clone(),
+                    embedded,
+                    total: pending_chunks,
+                },
+            );
+        }
+        files.insert(relative, file);
+    }
+
+    let index = EmbeddingIndex {
+        version: INDEX_VERSION,
+        model: model.clone(),
+        files,
+    };
+    save_index(&project_path, &index)?;
+
+    let chunks = index.files.values().map(|f| f.chunks.len()).sum();
+    println!("✅ Indexed {} ({} chunks)", project_path, chunks);
+    Ok(IndexStats {
+        model,
+        files_indexed,
+        files_unchanged,
+        files_removed,
+        chunks,
+    })
+}
+
+pub(crate) async fn retrieve(
+    project_path: &str,
+    query: &str,
+    k: usize,
+) -> Result<Vec<RetrievedChunk>, String> {
+    let index = load_index(project_path).ok_or_else(|| {
+        format!(
+            "No embeddings index for {}; run index_project first",
+            project_path
+        )
+    })?;
+
+    let client = embedding_client()?;
+    let ollama = OllamaProvider::new(load_provider_config().endpoint("ollama"));
+    let query_vector = embed_texts(&ollama, &client, &index.model, vec![query.to_string()])
+        .await?
+        .pop()
+        .unwrap_or_default();
+
+    let mut scored: Vec<RetrievedChunk> = index
+        .files
+        .iter()
+        .flat_map(|(path, file)| {
+            file.chunks.iter().map(|chunk| RetrievedChunk {
+                path: path.clone(),
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                content: chunk.content.clone(),
+                score: chunk
+                    .vector
+                    .iter()
+                    .zip(&query_vector)
+                    .map(|(a, b)| a * b)
+                    .sum(),
+            })
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scored.truncate(k);
+    Ok(scored)
+}
+
+/// The `k` project chunks most similar to `query`, best first.
+#[tauri::command]
+pub async fn retrieve_context(
+    project_path: String,
+    query: String,
+    k: Option<usize>,
+) -> Result<Vec<RetrievedChunk>, String> {
+    retrieve(&project_path, &query, k.unwrap_or(DEFAULT_TOP_K)).await
+}
+
+/// Puts the retrieved chunks ahead of the user's prompt, each labelled with
+/// its file and line range so the model can cite them.
+pub(crate) fn prepend_context(prompt: &str, chunks: &[RetrievedChunk]) -> String {
+    if chunks.is_empty() {
+        return prompt.to_string();
+    }
+
+    let mut text = String::from(
+        "Relevant code from the project (cite it as path:start-end when you use it):\n\n",
+    );
+    for chunk in chunks {
+        text.push_str(&format!(
+            "{}:{}-{}\n```\n{}\n```\n\n",
+            chunk.path, chunk.start_line, chunk.end_line, chunk.content
+        ));
+    }
+    text.push_str("---\n\n");
+    text.push_str(prompt);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_overlap_and_cover_every_line() {
+        let content: Vec<String> = (1..=100).map(|n| format!("line {}", n)).collect();
+        let chunks = chunk_lines(&content.join("\n"));
+
+        let ranges: Vec<(usize, usize)> = chunks.iter().map(|(s, e, _)| (*s, *e)).collect();
+        assert_eq!(ranges, [(1, 40), (33, 72), (65, 100)]);
+        for (start, end, text) in &chunks {
+            assert_eq!(
+                text.lines().next(),
+                Some(format!("line {}", start).as_str())
+            );
+            assert_eq!(text.lines().last(), Some(format!("line {}", end).as_str()));
+        }
+    }
+
+    #[test]
+    fn truncated_chunks_report_the_lines_they_hold() {
+        // 10 lines of 1000 characters; only the first 4 (and part of a 5th) fit
+        let long_line = "x".repeat(999);
+        let content = [long_line.as_str(); 10].join("\n");
+        let chunks = chunk_lines(&content);
+
+        for (start, end, text) in &chunks {
+            assert!(text.chars().count() <= MAX_CHUNK_CHARS);
+            assert_eq!(text.lines().count(), end - start + 1);
+        }
+        // Every line still starts in some chunk
+        let covered: Vec<usize> = chunks.iter().flat_map(|(s, e, _)| *s..=*e).collect();
+        assert!((1..=10).all(|line| covered.contains(&line)));
+        assert_eq!(chunks.last().map(|(_, end, _)| *end), Some(10));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::filesystem::{resolve_in_project, FileConflict, FileEntry};
+use std::fs;
+use std::path::Path;
+
+// Files without an extension that are still obviously file names
+const BARE_FILE_NAMES: &[&str] = &[
+    "Dockerfile",
+    "Makefile",
+    "Procfile",
+    "LICENSE",
+    ".gitignore",
+];
+
+// "src/main.rs" yes; "rust", "Example:" or a URL no
+fn looks_like_path(candidate: &str) -> bool {
+    if candidate.is_empty() || candidate.contains(char::is_whitespace) || candidate.contains("://")
+    {
+        return false;
+    }
+    let file_name = candidate.rsplit('/').next().unwrap_or(candidate);
+    BARE_FILE_NAMES.contains(&file_name)
+        || file_name.rsplit_once('.').is_some_and(|(stem, ext)| {
+            !ext.is_empty() && (!stem.is_empty() || file_name.starts_with('.'))
+        })
+}
+
+// Path from a fence info string: "rust src/main.rs", "src/main.rs", "tsx title=\"src/App.tsx\""
+fn path_from_info(info: &str) -> Option<String> {
+    info.split_whitespace()
+        .map(|token| {
+            let value = token
+                .split_once('=')
+                .filter(|(key, _)| matches!(*key, "title" | "file" | "filename" | "path"))
+                .map_or(token, |(_, value)| value);
+            value.trim_matches(|c| c == '"' || c == '\'')
+        })
+        .find(|token| looks_like_path(token))
+        .map(str::to_string)
+}
+
+// Path from the line just above a fence: "// src/App.tsx", "**src/App.tsx**", "File: `main.py`"
+fn path_from_hint_line(line: &str) -> Option<String> {
+    let mut hint = line.trim();
+    for prefix in ["//", "#", "--", "<!--"] {
+        hint = hint.trim_start_matches(prefix).trim();
+    }
+    hint = hint.trim_end_matches("-->").trim();
+
+    let lower = hint.to_lowercase();
+    for label in ["file:", "filename:", "path:"] {
+        if lower.starts_with(label) {
+            hint = hint[label.len()..].trim();
+            break;
+        }
+    }
+
+    let hint = hint
+        .trim_end_matches(':')
+        .trim_matches(|c| c == '`' || c == '*' || c == '"' || c == '\'');
+    looks_like_path(hint).then(|| hint.to_string())
+}
+
+struct CodeBlock {
+    path: String,
+    content: String,
+}
+
+fn fence_len(line: &str) -> usize {
+    line.chars().take_while(|&c| c == '`').count()
+}
+
+fn parse_code_blocks(text: &str) -> Vec<CodeBlock> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let opening = lines[i].trim_start();
+        let ticks = fence_len(opening);
+        if ticks < 3 {
+            i += 1;
+            continue;
+        }
+
+        let info = &opening[ticks..];
+        let hint = lines[..i]
+            .iter()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .and_then(|l| path_from_hint_line(l));
+        let path = path_from_info(info).or(hint);
+
+        // A closing fence is at least as long as the opening one
+        let start = i + 1;
+        let mut end = start;
+        while end < lines.len() {
+            let line = lines[end].trim();
+            if fence_len(line) >= ticks && line.chars().all(|c| c == '`') {
+                break;
+            }
+            end += 1;
+        }
+
+        if let Some(path) = path {
+            let mut content = lines[start..end].join("\n");
+            content.push('\n');
+            blocks.push(CodeBlock { path, content });
+        }
+        i = end + 1;
+    }
+
+    blocks
+}
+
+/// Finds fenced code blocks that name their file, either in the fence
+/// (```` ```rust src/main.rs ````) or on the line above it (`// src/main.rs`), and
+/// returns them as entries under `project_root`, flagging any that clash with
+/// existing files or with each other.
+#[tauri::command]
+pub async fn extract_files_from_response(
+    text: String,
+    project_root: String,
+) -> Result<Vec<FileEntry>, String> {
+    let root = Path::new(&project_root);
+    let mut files: Vec<FileEntry> = Vec::new();
+
+    for block in parse_code_blocks(&text) {
+        // Models like to write "/src/main.rs" or "./src/main.rs" for project files
+        let relative = Path::new(&block.path)
+            .strip_prefix(root)
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| block.path.clone());
+        let relative = relative.trim_start_matches("./").trim_start_matches('/');
+
+        let full_path = match resolve_in_project(root, relative) {
+            Ok(path) => path.to_string_lossy().to_string(),
+            Err(e) => {
+                println!("⚠️ Skipping code block: {}", e);
+                continue;
+            }
+        };
+
+        let mut conflict = match fs::read_to_string(&full_path) {
+            Ok(existing) if existing != block.content => Some(FileConflict::Exists),
+            _ => None,
+        };
+
+        if let Some(index) = files.iter().position(|f| f.path == full_path) {
+            files.remove(index);
+            conflict = Some(FileConflict::Duplicate);
+        }
+
+        files.push(FileEntry {
+            path: full_path,
+            content: block.content,
+            conflict,
+        });
+    }
+
+    Ok(files)
+}
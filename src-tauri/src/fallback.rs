@@ -0,0 +1,393 @@
+use crate::config::{ProviderConfig, RetryPolicy};
+use crate::history::{fit_request, HistoryTrim};
+use crate::providers::{ChatRequest, GenerationSummary, LlmProvider, ProviderRegistry};
+use reqwest::Client;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+// One provider/model pair a generation may run on
+pub(crate) struct Route {
+    pub provider: String,
+    pub model: String,
+    pub backend: Arc<dyn LlmProvider>,
+}
+
+// Reported with `generate-finished` so the UI can show where the answer came from
+#[derive(Debug, Serialize, Clone)]
+pub struct ProviderUsed {
+    pub provider: String,
+    pub model: String,
+    pub attempts: u32, // across all providers tried
+}
+
+/// The requested provider followed by the configured fallbacks. Fallbacks that
+/// aren't registered or don't name a model are skipped rather than failing the
+/// generation.
+pub(crate) fn resolve_routes(
+    registry: &ProviderRegistry,
+    config: &ProviderConfig,
+    provider: &str,
+    model: &str,
+) -> Result<Vec<Route>, String> {
+    let mut routes = vec![Route {
+        provider: provider.to_string(),
+        model: model.to_string(),
+        backend: registry.get(provider)?,
+    }];
+
+    for fallback in &config.fallbacks {
+        let model = match &fallback.model {
+            Some(model) => model.clone(),
+            None => {
+                println!(
+                    "⚠️ Skipping fallback {}: no model set for it",
+                    fallback.provider
+                );
+                continue;
+            }
+        };
+        if routes
+            .iter()
+            .any(|r| r.provider == fallback.provider && r.model == model)
+        {
+            continue;
+        }
+        match registry.get(&fallback.provider) {
+            Ok(backend) => routes.push(Route {
+                provider: fallback.provider.clone(),
+                model,
+                backend,
+            }),
+            Err(e) => println!("⚠️ Skipping fallback: {}", e),
+        }
+    }
+
+    Ok(routes)
+}
+
+fn backoff(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+    let ms = policy
+        .initial_backoff_ms
+        .saturating_mul(factor)
+        .min(policy.max_backoff_ms);
+    Duration::from_millis(ms)
+}
+
+/// Streams `request` on the first route that works. Connection failures and
+/// 5xx responses are retried with exponential backoff, then the next route is
+/// tried. Other errors (a missing model, a bad API key) end the chain when the
+/// requested provider reports them, since falling back would hide a mistake
+/// the user has to fix; a fallback reporting them is skipped. Any failure
+/// after a token has been streamed is final, so the user never sees the start
+/// of an answer twice.
+pub(crate) async fn stream_with_fallback(
+    client: &Client,
+    routes: &[Route],
+    request: &ChatRequest,
+    policy: &RetryPolicy,
+    on_token: &mut (dyn FnMut(String) + Send),
+) -> Result<(GenerationSummary, HistoryTrim, ProviderUsed), String> {
+    let mut failures = Vec::new();
+    let mut attempts = 0;
+
+    'routes: for (index, route) in routes.iter().enumerate() {
+        if index > 0 {
+            println!(
+                "↪️ Falling back from {} to {} ({})",
+                routes[index - 1].provider,
+                route.provider,
+                route.model
+            );
+        }
+
+        let mut request = request.clone();
+        request.model = route.model.clone();
+        let trim = fit_request(route.backend.as_ref(), client, &mut request).await;
+
+        for attempt in 1..=policy.max_attempts.max(1) {
+            attempts += 1;
+            let mut streamed = false;
+            let result = {
+                let mut forward = |token: String| {
+                    streamed |= !token.is_empty();
+                    on_token(token);
+                };
+                route
+                    .backend
+                    .stream_chat(client, &request, &mut forward)
+                    .await
+            };
+
+            let error = match result {
+                Ok(summary) => {
+                    let used = ProviderUsed {
+                        provider: route.provider.clone(),
+                        model: route.model.clone(),
+                        attempts,
+                    };
+                    return Ok((summary, trim, used));
+                }
+                Err(e) if streamed => return Err(e.into()),
+                Err(e) if !e.is_retryable() => {
+                    if index > 0 {
+                        println!("⚠️ Skipping fallback {}: {}", route.provider, e);
+                    }
+                    failures.push((route.provider.clone(), e.message));
+                    if index == 0 {
+                        break 'routes;
+                    }
+                    continue 'routes;
+                }
+                Err(e) => e.message,
+            };
+
+            if attempt < policy.max_attempts {
+                let delay = backoff(policy, attempt);
+                println!(
+                    "🔁 {} (attempt {}/{}), retrying in {}ms",
+                    error,
+                    attempt,
+                    policy.max_attempts,
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+            } else {
+                failures.push((route.provider.clone(), error));
+            }
+        }
+    }
+
+    // With a single provider, keep its error as is
+    if let [(_, error)] = failures.as_slice() {
+        return Err(error.clone());
+    }
+    let failures: Vec<String> = failures
+        .iter()
+        .map(|(provider, error)| format!("{}: {}", provider, error))
+        .collect();
+    Err(format!("All providers failed ({})", failures.join("; ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{FallbackProvider, ProviderEndpoint};
+    use crate::generations::ActiveGenerations;
+    use crate::models::ollama::{ChatMessage, Role};
+    use crate::providers::mock::{MockProvider, MockTranscript, MockTurn, ECHO_MODEL};
+    use crate::providers::ollama::OllamaProvider;
+
+    const QUICK_RETRIES: RetryPolicy = RetryPolicy {
+        max_attempts: 2,
+        initial_backoff_ms: 1,
+        max_backoff_ms: 1,
+    };
+
+    fn route(provider: &str, model: &str, backend: impl LlmProvider + 'static) -> Route {
+        Route {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            backend: Arc::new(backend),
+        }
+    }
+
+    fn request(prompt: &str) -> ChatRequest {
+        ChatRequest {
+            model: String::new(),
+            messages: vec![ChatMessage::new(Role::User, prompt)],
+            options: Default::default(),
+            tools: Vec::new(),
+        }
+    }
+
+    fn replaying(turn: MockTurn) -> MockProvider {
+        MockProvider::replaying(MockTranscript { turns: vec![turn] })
+    }
+
+    #[tokio::test]
+    async fn streams_on_the_first_route() {
+        let routes = [route("mock", ECHO_MODEL, MockProvider::default())];
+        let mut tokens = Vec::new();
+
+        let (summary, _, used) = stream_with_fallback(
+            &Client::new(),
+            &routes,
+            &request("ping"),
+            &QUICK_RETRIES,
+            &mut |token| tokens.push(token),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tokens.concat(), "You said: ping");
+        assert_eq!(summary.finish_reason.as_deref(), Some("stop"));
+        assert_eq!((used.provider.as_str(), used.attempts), ("mock", 1));
+    }
+
+    #[tokio::test]
+    async fn retries_an_unreachable_provider_then_falls_back() {
+        let unreachable = OllamaProvider::new(ProviderEndpoint::new("http://127.0.0.1:1"));
+        let routes = [
+            route("ollama", "llama3", unreachable),
+            route("mock", ECHO_MODEL, MockProvider::default()),
+        ];
+        let mut tokens = Vec::new();
+
+        let (_, _, used) = stream_with_fallback(
+            &Client::new(),
+            &routes,
+            &request("ping"),
+            &QUICK_RETRIES,
+            &mut |token| tokens.push(token),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tokens.concat(), "You said: ping");
+        assert_eq!((used.provider.as_str(), used.attempts), ("mock", 3));
+    }
+
+    #[tokio::test]
+    async fn skips_a_fallback_that_rejects_the_request() {
+        let unreachable = OllamaProvider::new(ProviderEndpoint::new("http://127.0.0.1:1"));
+        let rejecting = MockTurn {
+            error: Some("No API key stored for OpenRouter".to_string()),
+            ..Default::default()
+        };
+        let routes = [
+            route("ollama", "llama3", unreachable),
+            route("openrouter", "transcript", replaying(rejecting)),
+            route("mock", ECHO_MODEL, MockProvider::default()),
+        ];
+        let mut tokens = Vec::new();
+
+        let (_, _, used) = stream_with_fallback(
+            &Client::new(),
+            &routes,
+            &request("ping"),
+            &QUICK_RETRIES,
+            &mut |token| tokens.push(token),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tokens.concat(), "You said: ping");
+        assert_eq!((used.provider.as_str(), used.attempts), ("mock", 4));
+    }
+
+    #[tokio::test]
+    async fn stops_when_the_requested_provider_rejects_the_request() {
+        let rejecting = MockTurn {
+            error: Some("model not found".to_string()),
+            ..Default::default()
+        };
+        let routes = [
+            route("mock", "transcript", replaying(rejecting)),
+            route("backup", ECHO_MODEL, MockProvider::default()),
+        ];
+        let mut tokens = Vec::new();
+
+        let error = stream_with_fallback(
+            &Client::new(),
+            &routes,
+            &request("ping"),
+            &QUICK_RETRIES,
+            &mut |token| tokens.push(token),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error, "Mock error: model not found");
+        assert!(tokens.is_empty());
+    }
+
+    #[test]
+    fn skips_fallbacks_to_other_providers_without_a_model() {
+        let registry = ProviderRegistry::default();
+        let config = ProviderConfig {
+            fallbacks: vec![
+                FallbackProvider {
+                    provider: "openrouter".to_string(),
+                    model: None,
+                },
+                FallbackProvider {
+                    provider: "lmstudio".to_string(),
+                    model: Some("qwen2.5-coder".to_string()),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let routes = resolve_routes(&registry, &config, "ollama", "llama3").unwrap();
+        let routes: Vec<_> = routes
+            .iter()
+            .map(|r| (r.provider.as_str(), r.model.as_str()))
+            .collect();
+
+        assert_eq!(
+            routes,
+            [("ollama", "llama3"), ("lmstudio", "qwen2.5-coder")]
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_an_error_after_tokens_were_streamed() {
+        let failing = MockTurn {
+            text: Some("half an".to_string()),
+            error: Some("connection reset".to_string()),
+            ..Default::default()
+        };
+        let routes = [
+            route("mock", "transcript", replaying(failing)),
+            route("backup", ECHO_MODEL, MockProvider::default()),
+        ];
+        let mut tokens = Vec::new();
+
+        let error = stream_with_fallback(
+            &Client::new(),
+            &routes,
+            &request("ping"),
+            &QUICK_RETRIES,
+            &mut |token| tokens.push(token),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error, "Mock error: connection reset");
+        assert_eq!(tokens.concat(), "half an");
+    }
+
+    #[tokio::test]
+    async fn stops_streaming_when_cancelled() {
+        let slow = MockTurn {
+            text: Some("one two three four five six seven eight".to_string()),
+            delay_ms: 20,
+            ..Default::default()
+        };
+        let routes = [route("mock", "transcript", replaying(slow))];
+        let generations = ActiveGenerations::default();
+        let (request_id, cancelled) = generations.start();
+        let mut tokens = Vec::new();
+
+        let client = Client::new();
+        let request = request("count");
+        let mut forward = |token| tokens.push(token);
+        let stream = stream_with_fallback(&client, &routes, &request, &QUICK_RETRIES, &mut forward);
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(70)).await;
+            generations.cancel(&request_id)
+        };
+
+        // The same calls `generate_code` and `cancel_generation` make
+        let (outcome, was_running) =
+            tokio::join!(generations.run(&request_id, cancelled, stream), cancel);
+
+        assert!(was_running);
+        assert!(outcome.is_none());
+        assert!(!tokens.is_empty() && tokens.len() < 8, "{:?}", tokens);
+        // Finished generations are forgotten, so a second cancel finds nothing
+        assert!(!generations.cancel(&request_id));
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileNode {
@@ -14,6 +14,18 @@ pub struct FileNode {
 pub struct FileEntry {
     pub path: String,
     pub content: String,
+    // Set by extract_files_from_response when writing this entry needs a second look
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<FileConflict>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileConflict {
+    // The file already exists with different content
+    Exists,
+    // The response contained this path more than once; the last block wins
+    Duplicate,
 }
 
 #[allow(dead_code)]
@@ -24,6 +36,40 @@ pub struct ProjectStructure {
     pub files: Vec<FileEntry>,
 }
 
+/// Resolves a path given relative to `root`, refusing anything that climbs out of it,
+/// whether through `..` or through a symlink inside the project.
+pub(crate) fn resolve_in_project(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let relative_path = Path::new(relative);
+    let escapes = relative_path.components().any(|c| {
+        matches!(
+            c,
+            Component::ParentDir | Component::RootDir | Component::Prefix(_)
+        )
+    });
+    if escapes {
+        return Err(format!("Path must stay inside the project: {}", relative));
+    }
+    let path = root.join(relative_path);
+
+    // Nothing exists under a missing root yet, so nothing there can link out of it
+    let Ok(canonical_root) = root.canonicalize() else {
+        return Ok(path);
+    };
+    // The deepest part that exists decides where the rest will land. A dangling
+    // symlink counts as existing, and fails to canonicalize below.
+    let existing = path
+        .ancestors()
+        .find(|p| p.symlink_metadata().is_ok())
+        .unwrap_or(root);
+    let resolved = existing
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", relative, e))?;
+    if !resolved.starts_with(&canonical_root) {
+        return Err(format!("Path must stay inside the project: {}", relative));
+    }
+    Ok(path)
+}
+
 #[tauri::command]
 pub async fn create_project_folder(path: String, name: String) -> Result<String, String> {
     let project_path = Path::new(&path).join(&name);
@@ -149,3 +195,53 @@ pub async fn create_directory(path: String) -> Result<(), String> {
     println!("📁 Created directory: {}", path);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh directory holding `project/` and a sibling `outside/`
+    fn sandbox(name: &str) -> (PathBuf, PathBuf) {
+        let base = std::env::temp_dir().join(format!("resolve_in_project_{}", name));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("project/src")).unwrap();
+        fs::create_dir_all(base.join("outside")).unwrap();
+        (base.join("project"), base.join("outside"))
+    }
+
+    #[test]
+    fn resolves_existing_and_new_paths_inside_the_project() {
+        let (root, _) = sandbox("inside");
+        fs::write(root.join("src/main.rs"), "").unwrap();
+
+        assert_eq!(
+            resolve_in_project(&root, "src/main.rs").unwrap(),
+            root.join("src/main.rs")
+        );
+        assert_eq!(
+            resolve_in_project(&root, "src/new/mod.rs").unwrap(),
+            root.join("src/new/mod.rs")
+        );
+        assert!(resolve_in_project(&root, "../outside/x").is_err());
+        assert!(resolve_in_project(&root, "/etc/passwd").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_symlinks_that_lead_out_of_the_project() {
+        use std::os::unix::fs::symlink;
+
+        let (root, outside) = sandbox("symlinks");
+        fs::write(outside.join("secret"), "").unwrap();
+        symlink(&outside, root.join("linked_dir")).unwrap();
+        symlink(outside.join("secret"), root.join("linked_file")).unwrap();
+        symlink(outside.join("missing"), root.join("dangling")).unwrap();
+        symlink(root.join("src"), root.join("internal")).unwrap();
+
+        assert!(resolve_in_project(&root, "linked_dir/secret").is_err());
+        assert!(resolve_in_project(&root, "linked_dir/new.rs").is_err());
+        assert!(resolve_in_project(&root, "linked_file").is_err());
+        assert!(resolve_in_project(&root, "dangling").is_err());
+        assert!(resolve_in_project(&root, "internal/lib.rs").is_ok());
+    }
+}
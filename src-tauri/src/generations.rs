@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+// In-flight generations (keyed by the request ID handed back to the frontend)
+// and model pulls (keyed by "pull:<model>")
+#[derive(Default)]
+pub struct ActiveGenerations {
+    next_id: AtomicU64,
+    cancellers: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+impl ActiveGenerations {
+    /// Registers a new generation and returns its ID plus a receiver that fires on cancel.
+    pub fn start(&self) -> (String, oneshot::Receiver<()>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let request_id = format!("gen-{}", id);
+        let (tx, rx) = oneshot::channel();
+
+        self.cancellers
+            .lock()
+            .unwrap()
+            .insert(request_id.clone(), tx);
+
+        (request_id, rx)
+    }
+
+    /// Registers work under a caller-chosen key. Fails if that key is already in flight.
+    pub fn track(&self, key: &str) -> Result<oneshot::Receiver<()>, String> {
+        let mut cancellers = self.cancellers.lock().unwrap();
+        if cancellers.contains_key(key) {
+            return Err(format!("Already in progress: {}", key));
+        }
+
+        let (tx, rx) = oneshot::channel();
+        cancellers.insert(key.to_string(), tx);
+        Ok(rx)
+    }
+
+    pub fn finish(&self, request_id: &str) {
+        self.cancellers.lock().unwrap().remove(request_id);
+    }
+
+    /// Drives `work` until it completes, or until the generation is cancelled
+    /// (`None`), and forgets the generation either way.
+    pub async fn run<T>(
+        &self,
+        request_id: &str,
+        cancelled: oneshot::Receiver<()>,
+        work: impl Future<Output = T>,
+    ) -> Option<T> {
+        let outcome = tokio::select! {
+            res = work => Some(res),
+            _ = cancelled => None,
+        };
+        self.finish(request_id);
+        outcome
+    }
+
+    /// Signals the generation to stop. Returns false if it already finished.
+    pub fn cancel(&self, request_id: &str) -> bool {
+        match self.cancellers.lock().unwrap().remove(request_id) {
+            Some(tx) => tx.send(()).is_ok(),
+            None => false,
+        }
+    }
+}
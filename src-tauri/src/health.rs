@@ -0,0 +1,202 @@
+use crate::config::load_provider_config;
+use crate::providers::{LlmProvider, LoadedModel, ProviderRegistry};
+use futures_util::future::join_all;
+use reqwest::Client;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+// Long enough for a cold local server, short enough for a dashboard refresh
+const DEFAULT_PROBE_TIMEOUT_MS: u64 = 3000;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProviderHealth {
+    pub id: String,
+    pub name: String,
+    pub endpoint: Option<String>,
+    pub healthy: bool,
+    pub latency_ms: Option<u64>, // of the health check alone
+    pub version: Option<String>,
+    pub models_available: Option<usize>,
+    pub loaded_models: Vec<LoadedModel>,
+    pub error: Option<String>,      // why the provider is unhealthy
+    pub info_error: Option<String>, // why version and models couldn't be read
+}
+
+async fn probe(
+    client: &Client,
+    id: String,
+    provider: Arc<dyn LlmProvider>,
+    timeout: Duration,
+) -> ProviderHealth {
+    let endpoint = load_provider_config().endpoint(&id).base_url;
+    let mut health = ProviderHealth {
+        name: provider.name().to_string(),
+        id,
+        endpoint: (!endpoint.is_empty()).then_some(endpoint),
+        healthy: false,
+        latency_ms: None,
+        version: None,
+        models_available: None,
+        loaded_models: Vec::new(),
+        error: None,
+        info_error: None,
+    };
+
+    let started = Instant::now();
+    let checked = tokio::time::timeout(timeout, provider.health_check(client)).await;
+    match checked {
+        Err(_) => {
+            health.error = Some(format!("No response within {}ms", timeout.as_millis()));
+            return health;
+        }
+        Ok(Err(e)) => {
+            health.error = Some(e);
+            return health;
+        }
+        Ok(Ok(())) => {
+            health.healthy = true;
+            health.latency_ms = Some(started.elapsed().as_millis() as u64);
+        }
+    }
+
+    // Details only: a backend that answers stays healthy even if, e.g., a bad
+    // API key keeps it from listing models. Gets its own timeout so a slow
+    // health check doesn't leave it no time.
+    match tokio::time::timeout(timeout, provider.backend_info(client)).await {
+        Ok(Ok(info)) => {
+            health.version = info.version;
+            health.models_available = info.models_available;
+            health.loaded_models = info.loaded_models;
+        }
+        Ok(Err(e)) => health.info_error = Some(e),
+        Err(_) => {
+            health.info_error = Some(format!(
+                "Listing models took over {}ms",
+                timeout.as_millis()
+            ))
+        }
+    }
+    health
+}
+
+/// Probes every registered provider (or just `providers`) concurrently and
+/// reports reachability, latency, version and models for each, with the
+/// reason when one can't be used.
+#[tauri::command]
+pub async fn check_providers(
+    registry: State<'_, ProviderRegistry>,
+    providers: Option<Vec<String>>,
+    timeout_ms: Option<u64>,
+) -> Result<Vec<ProviderHealth>, String> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_PROBE_TIMEOUT_MS));
+    let client = Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let targets = match providers {
+        Some(ids) => ids
+            .into_iter()
+            .map(|id| registry.get(&id).map(|provider| (id, provider)))
+            .collect::<Result<Vec<_>, String>>()?,
+        // The dev-only mock isn't a backend anyone needs to set up
+        None => registry
+            .all()
+            .into_iter()
+            .filter(|(id, _)| id != "mock")
+            .collect(),
+    };
+
+    let started = Instant::now();
+    let report = join_all(
+        targets
+            .into_iter()
+            .map(|(id, provider)| probe(&client, id, provider, timeout)),
+    )
+    .await;
+
+    let healthy = report.iter().filter(|p| p.healthy).count();
+    println!(
+        "🩺 {}/{} providers healthy ({}ms)",
+        healthy,
+        report.len(),
+        started.elapsed().as_millis()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ollama::ModelInfo;
+    use crate::providers::{BackendInfo, ChatRequest, GenerationSummary, ProviderError};
+    use async_trait::async_trait;
+
+    const UNAUTHORIZED: &str = "Unlisted API Error: 401 Unauthorized";
+
+    // Answers its health check but takes `info_delay` and then fails to list models
+    struct Unlisted {
+        info_delay: Duration,
+    }
+
+    #[async_trait]
+    impl LlmProvider for Unlisted {
+        fn name(&self) -> &str {
+            "Unlisted"
+        }
+
+        async fn stream_chat(
+            &self,
+            _client: &Client,
+            _request: &ChatRequest,
+            _on_token: &mut (dyn FnMut(String) + Send),
+        ) -> Result<GenerationSummary, ProviderError> {
+            Err("unused".into())
+        }
+
+        async fn list_models(&self, _client: &Client) -> Result<Vec<ModelInfo>, String> {
+            Err(UNAUTHORIZED.to_string())
+        }
+
+        async fn health_check(&self, _client: &Client) -> Result<(), String> {
+            Ok(())
+        }
+
+        async fn backend_info(&self, client: &Client) -> Result<BackendInfo, String> {
+            tokio::time::sleep(self.info_delay).await;
+            self.list_models(client)
+                .await
+                .map(|_| BackendInfo::default())
+        }
+    }
+
+    async fn probe_unlisted(info_delay: Duration) -> ProviderHealth {
+        let provider = Arc::new(Unlisted { info_delay });
+        let timeout = Duration::from_millis(100);
+        probe(&Client::new(), "unlisted".to_string(), provider, timeout).await
+    }
+
+    #[tokio::test]
+    async fn stays_healthy_when_details_fail() {
+        let health = probe_unlisted(Duration::ZERO).await;
+
+        assert!(health.healthy);
+        assert!(health.latency_ms.is_some());
+        assert_eq!(health.error, None);
+        assert_eq!(health.info_error.as_deref(), Some(UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn stays_healthy_when_details_time_out() {
+        let health = probe_unlisted(Duration::from_secs(5)).await;
+
+        assert!(health.healthy);
+        assert_eq!(health.error, None);
+        assert_eq!(
+            health.info_error.as_deref(),
+            Some("Listing models took over 100ms")
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use crate::config::{load_provider_config, ProviderConfig};
+use crate::models::ollama::{ChatMessage, Role};
+use crate::providers::{ChatRequest, LlmProvider};
+use reqwest::Client;
+use serde::Serialize;
+
+// Room kept free for the reply when the request doesn't set max_tokens
+const DEFAULT_RESPONSE_RESERVE: u32 = 1024;
+// Role markers and separators the chat template adds around each message
+const MESSAGE_OVERHEAD: usize = 4;
+// Vision models spend a few hundred tokens per image; err on the high side
+const IMAGE_TOKENS: usize = 768;
+// How much of each dropped user request survives in the recap
+const RECAP_SNIPPET_CHARS: usize = 120;
+
+/// Rough token count: ~4 characters per token holds up well enough for English and code.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+fn message_tokens(message: &ChatMessage) -> usize {
+    estimate_tokens(&message.text()) + message.images().len() * IMAGE_TOKENS + MESSAGE_OVERHEAD
+}
+
+// What had to go to make the request fit; sent with `generate-finished`
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct HistoryTrim {
+    pub context_length: Option<u32>, // unknown windows aren't trimmed to
+    pub estimated_prompt_tokens: usize,
+    pub dropped_messages: usize,
+    pub dropped_tokens: usize,
+    pub summarized: bool,
+}
+
+/// Context window for the request: `num_ctx` if set, then the user's per-model
+/// override, then whatever the backend reports.
+pub async fn resolve_context_length(
+    backend: &dyn LlmProvider,
+    client: &Client,
+    config: &ProviderConfig,
+    request: &ChatRequest,
+) -> Option<u32> {
+    if let Some(num_ctx) = request.options.num_ctx {
+        return Some(num_ctx);
+    }
+    if let Some(tokens) = config.context_lengths.get(&request.model) {
+        return Some(*tokens);
+    }
+    backend.context_length(client, &request.model).await
+}
+
+/// Trims `request.messages` in place to fit the model's context window.
+pub async fn fit_request(
+    backend: &dyn LlmProvider,
+    client: &Client,
+    request: &mut ChatRequest,
+) -> HistoryTrim {
+    let config = load_provider_config();
+    // A guessed window could cut a long-context model down to a fraction of
+    // what it takes, so leave the history alone and let the backend decide
+    let Some(context_length) = resolve_context_length(backend, client, &config, request).await
+    else {
+        return HistoryTrim {
+            estimated_prompt_tokens: request.messages.iter().map(message_tokens).sum(),
+            ..Default::default()
+        };
+    };
+    // Ollama otherwise loads the model with its own default window and silently
+    // truncates the prompt we just budgeted for; other backends ignore num_ctx
+    request.options.num_ctx.get_or_insert(context_length);
+
+    let messages = std::mem::take(&mut request.messages);
+    let (messages, trim) = fit_to_context(messages, context_length, request.options.max_tokens);
+    request.messages = messages;
+
+    if trim.dropped_messages > 0 {
+        println!(
+            "✂️  Dropped {} history messages (~{} tokens) to fit {}'s {}-token context",
+            trim.dropped_messages, trim.dropped_tokens, request.model, context_length
+        );
+    }
+
+    trim
+}
+
+/// Drops the oldest history turns until the messages fit the context window, leaving
+/// room for the reply. Leading system messages and the final prompt are always kept,
+/// and the dropped user requests are recapped in a system message when that still fits.
+pub fn fit_to_context(
+    messages: Vec<ChatMessage>,
+    context_length: u32,
+    max_tokens: Option<u32>,
+) -> (Vec<ChatMessage>, HistoryTrim) {
+    // Never let a large max_tokens eat more than half the window
+    let reserve = max_tokens
+        .unwrap_or(DEFAULT_RESPONSE_RESERVE)
+        .min(context_length / 2);
+    let budget = (context_length - reserve) as usize;
+
+    let head_len = messages
+        .iter()
+        .take_while(|m| m.role == Role::System)
+        .count();
+    let mut history = messages;
+    let head: Vec<ChatMessage> = history.drain(..head_len).collect();
+    let prompt = history.pop();
+
+    let fixed: usize =
+        head.iter().map(message_tokens).sum::<usize>() + prompt.as_ref().map_or(0, message_tokens);
+    let mut history_tokens: usize = history.iter().map(message_tokens).sum();
+
+    let mut dropped = Vec::new();
+    while !history.is_empty() && fixed + history_tokens > budget {
+        let message = history.remove(0);
+        history_tokens -= message_tokens(&message);
+        dropped.push(message);
+
+        // Don't leave the history starting on an assistant reply; some APIs reject that
+        while history.first().is_some_and(|m| m.role != Role::User) {
+            let message = history.remove(0);
+            history_tokens -= message_tokens(&message);
+            dropped.push(message);
+        }
+    }
+
+    let mut trim = HistoryTrim {
+        context_length: Some(context_length),
+        estimated_prompt_tokens: fixed + history_tokens,
+        dropped_messages: dropped.len(),
+        dropped_tokens: dropped.iter().map(message_tokens).sum(),
+        summarized: false,
+    };
+
+    let recap = recap_dropped(&dropped)
+        .filter(|recap| trim.estimated_prompt_tokens + message_tokens(recap) <= budget);
+
+    let mut fitted = head;
+    if let Some(recap) = recap {
+        trim.estimated_prompt_tokens += message_tokens(&recap);
+        trim.summarized = true;
+        fitted.push(recap);
+    }
+    fitted.extend(history);
+    fitted.extend(prompt);
+
+    (fitted, trim)
+}
+
+// One line per dropped user request, so the model still knows what came before
+fn recap_dropped(dropped: &[ChatMessage]) -> Option<ChatMessage> {
+    let requests: Vec<String> = dropped
+        .iter()
+        .filter(|m| m.role == Role::User)
+        .map(|m| {
+            let text = m.text();
+            let line = text.lines().next().unwrap_or("").trim();
+            let snippet: String = line.chars().take(RECAP_SNIPPET_CHARS).collect();
+            if snippet.len() < line.len() {
+                format!("- {}…", snippet)
+            } else {
+                format!("- {}", snippet)
+            }
+        })
+        .collect();
+
+    if requests.is_empty() {
+        return None;
+    }
+
+    Some(ChatMessage::new(
+        Role::System,
+        format!(
+            "Earlier messages were removed to fit the context window. The user had asked:\n{}",
+            requests.join("\n")
+        ),
+    ))
+}
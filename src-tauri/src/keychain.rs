@@ -23,21 +23,31 @@ pub fn store_api_key(provider: String, key: String) -> Result<ApiKeyResult, Stri
     }
 }
 
+// The key itself stays in the backend; the UI only needs to know it's there
 #[tauri::command]
-pub fn get_api_key(provider: String) -> Result<String, String> {
+pub fn has_api_key(provider: String) -> Result<bool, String> {
+    Ok(load_api_key(&provider)?.is_some())
+}
+
+// Backend-side lookup so cloud providers never need the key to pass through the webview
+pub fn load_api_key(provider: &str) -> Result<Option<String>, String> {
     let service = format!("vibe-studio-{}", provider);
 
     match Entry::new(&service, "api-key") {
-        Ok(entry) => {
-            match entry.get_password() {
-                Ok(password) => Ok(password),
-                Err(_) => Ok(String::new()), // Return empty string if not found
-            }
-        }
+        Ok(entry) => match entry.get_password() {
+            Ok(password) if !password.is_empty() => Ok(Some(password)),
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(None),
+            // e.g. a locked keychain or denied access; "no key" would hide the fix
+            Err(e) => Err(format!("Failed to read API key: {}", e)),
+        },
         Err(e) => Err(format!("Failed to access keyring: {}", e)),
     }
 }
 
+pub fn require_api_key(provider: &str, display_name: &str) -> Result<String, String> {
+    load_api_key(provider)?.ok_or_else(|| format!("No API key stored for {}", display_name))
+}
+
 #[tauri::command]
 pub fn delete_api_key(provider: String) -> Result<ApiKeyResult, String> {
     let service = format!("vibe-studio-{}", provider);
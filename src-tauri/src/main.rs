@@ -1,25 +1,76 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod agent;
+mod attachments;
 mod commands;
+mod completion;
+mod config;
+mod edits;
+mod embeddings;
+mod extract;
+mod fallback;
 mod filesystem;
+mod generations;
 mod git;
+mod health;
+mod history;
 mod keychain;
 mod models;
+mod prompts;
+mod providers;
 mod search;
+mod sessions;
+// Test doubles for trying the app without a model; left out of release builds
+#[cfg(debug_assertions)]
+mod stub_server;
 mod terminal;
 
 fn main() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(providers::ProviderRegistry::from_config(
+            &config::load_provider_config(),
+        ))
+        .manage(generations::ActiveGenerations::default())
+        .manage(agent::ToolApprovals::default())
+        .manage(completion::InlineCompletions::default())
+        .manage(sessions::ConversationIndex::default());
+    #[cfg(debug_assertions)]
+    let builder = builder.manage(stub_server::StubServers::default());
+
+    builder
         .invoke_handler(tauri::generate_handler![
-            commands::check_ollama_status,
+            health::check_providers,
             commands::download_model,
+            commands::cancel_download,
             commands::save_project,
             commands::load_projects,
             commands::generate_code,
+            commands::cancel_generation,
+            commands::generate_text,
+            commands::list_models,
+            commands::show_model,
+            commands::delete_model,
+            agent::run_agent,
+            agent::approve_tool_call,
+            config::get_provider_config,
+            config::set_provider_endpoint,
+            config::reset_provider_endpoint,
+            config::set_model_context_length,
+            config::set_retry_policy,
+            config::set_fallback_providers,
+            edits::apply_edits,
+            extract::extract_files_from_response,
+            embeddings::index_project,
+            embeddings::retrieve_context,
+            completion::complete_inline,
+            #[cfg(debug_assertions)]
+            stub_server::start_stub_server,
+            #[cfg(debug_assertions)]
+            stub_server::stop_stub_server,
             filesystem::create_project_folder,
             filesystem::write_file,
             filesystem::read_file,
@@ -28,6 +79,17 @@ fn main() {
             filesystem::create_multiple_files,
             filesystem::rename_file,
             filesystem::create_directory,
+            prompts::list_system_prompts,
+            prompts::save_system_prompt,
+            prompts::delete_system_prompt,
+            sessions::create_session,
+            sessions::list_sessions,
+            sessions::load_session,
+            sessions::append_session_message,
+            sessions::rename_session,
+            sessions::fork_session,
+            sessions::delete_session,
+            sessions::search_conversations,
             git::git_init,
             git::git_status,
             git::git_add,
@@ -42,7 +104,7 @@ fn main() {
             terminal::get_shell_info,
             search::search_in_files,
             keychain::store_api_key,
-            keychain::get_api_key,
+            keychain::has_api_key,
             keychain::delete_api_key
         ])
         .run(tauri::generate_context!())
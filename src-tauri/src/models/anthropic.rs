@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+// Anthropic Messages API Request/Response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnthropicRequest {
+    pub model: String,
+    pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    pub messages: Vec<AnthropicMessage>,
+    pub stream: bool,
+    pub temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnthropicMessage {
+    pub role: String,
+    pub content: String,
+}
+
+// One `data:` payload of the streaming response; only the fields we consume are typed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnthropicStreamEvent {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub message: Option<AnthropicStreamMessage>,
+    pub delta: Option<AnthropicDelta>,
+    pub usage: Option<AnthropicUsage>,
+    pub error: Option<AnthropicError>,
+}
+
+// Carried by `message_start`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnthropicStreamMessage {
+    pub usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnthropicUsage {
+    pub input_tokens: Option<u32>,
+    pub output_tokens: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnthropicDelta {
+    #[serde(rename = "type")]
+    pub delta_type: Option<String>,
+    pub text: Option<String>,
+    pub stop_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnthropicError {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    pub message: String,
+}
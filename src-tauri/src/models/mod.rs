@@ -1 +1,3 @@
+pub mod anthropic;
+pub mod gemini;
 pub mod ollama;
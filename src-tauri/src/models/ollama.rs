@@ -3,47 +3,557 @@ use serde::{Deserialize, Serialize};
 // Unified Message Format (OpenAI-compatible)
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChatMessage {
+    pub role: Role,
+    pub content: MessageContent,
+    // Set on assistant messages that asked for tools to be run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    // Set on tool messages: which call this is the result of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+// A tool offered to the model, described by a JSON schema for its arguments
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::System => "system",
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::Tool => "tool",
+        }
+    }
+}
+
+// Either a plain string or a list of typed parts, like OpenAI's `content`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text {
+        text: String,
+    },
+    Image {
+        data: String, // base64, without a data: URL prefix
+        #[serde(default)]
+        mime_type: Option<String>,
+    },
+}
+
+impl ChatMessage {
+    pub fn new(role: Role, text: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: MessageContent::Text(text.into()),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+
+    /// The message's text, with multiple text parts joined by newlines.
+    pub fn text(&self) -> String {
+        match &self.content {
+            MessageContent::Text(text) => text.clone(),
+            MessageContent::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    ContentPart::Image { .. } => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    /// MIME type and base64 data of every image part.
+    pub fn images(&self) -> Vec<(&str, &str)> {
+        match &self.content {
+            MessageContent::Text(_) => Vec::new(),
+            MessageContent::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Image { data, mime_type } => {
+                        Some((mime_type.as_deref().unwrap_or("image/png"), data.as_str()))
+                    }
+                    ContentPart::Text { .. } => None,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let role = self.role.as_str();
+        if self.role == Role::Tool && self.tool_call_id.is_none() {
+            return Err("Tool message without a tool_call_id".to_string());
+        }
+        if !self.tool_calls.is_empty() {
+            if self.role != Role::Assistant {
+                return Err(format!(
+                    "Only assistant messages can call tools, not {}",
+                    role
+                ));
+            }
+            // The reply may be nothing but tool calls
+            return Ok(());
+        }
+
+        match &self.content {
+            MessageContent::Text(text) if text.trim().is_empty() => {
+                Err(format!("Empty {} message", role))
+            }
+            MessageContent::Text(_) => Ok(()),
+            MessageContent::Parts(parts) if parts.is_empty() => {
+                Err(format!("Empty {} message", role))
+            }
+            MessageContent::Parts(parts) => {
+                for part in parts {
+                    if let ContentPart::Image { data, .. } = part {
+                        if self.role != Role::User {
+                            return Err(format!(
+                                "Images are only allowed in user messages, not {}",
+                                role
+                            ));
+                        }
+                        if data.is_empty() {
+                            return Err("Image part has no data".to_string());
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Ollama Request/Response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaMessage {
     pub role: String,
+    #[serde(default)]
     pub content: String,
+    // Base64 images for multimodal models (llava, qwen-vl, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub images: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<OllamaToolCall>>,
+    // Ollama matches tool results to calls by name, not ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaToolCall {
+    pub function: OllamaFunctionCall,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaFunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+// Tool declaration shared by Ollama and the OpenAI API
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FunctionTool {
+    #[serde(rename = "type")]
+    pub tool_type: String,
+    pub function: ToolDefinition,
+}
+
+impl From<&ToolDefinition> for FunctionTool {
+    fn from(tool: &ToolDefinition) -> Self {
+        Self {
+            tool_type: "function".to_string(),
+            function: tool.clone(),
+        }
+    }
 }
 
-// Ollama Request/Response
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OllamaChatRequest {
     pub model: String,
-    pub messages: Vec<ChatMessage>,
+    pub messages: Vec<OllamaMessage>,
     pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<OllamaOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<FunctionTool>>,
+}
+
+// Model parameters, see the `options` field of Ollama's /api/chat
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct OllamaOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OllamaChatResponse {
+    #[serde(default)]
     pub model: String,
+    #[serde(default)]
     pub created_at: String,
-    pub message: Option<ChatMessage>,
+    pub message: Option<OllamaMessage>,
+    #[serde(default)]
     pub done: bool,
+    // Only present on the final (`done`) line
+    pub done_reason: Option<String>,
+    pub prompt_eval_count: Option<u32>,
+    pub eval_count: Option<u32>,
+    pub eval_duration: Option<u64>, // nanoseconds
+    // Sent instead of a message when the server fails mid-stream
+    pub error: Option<String>,
 }
 
 // LM Studio (OpenAI-compatible) Request/Response
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LMStudioRequest {
     pub model: String,
-    pub messages: Vec<ChatMessage>,
+    pub messages: Vec<LMStudioMessage>,
     pub stream: bool,
     pub temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<LMStudioStreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<FunctionTool>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioMessage {
+    pub role: String,
+    pub content: LMStudioContent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<LMStudioToolCall>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub function: LMStudioFunctionCall,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioFunctionCall {
+    pub name: String,
+    pub arguments: String, // JSON-encoded
+}
+
+// Plain text, or text and `image_url` parts for vision models
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LMStudioContent {
+    Text(String),
+    Parts(Vec<LMStudioContentPart>),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LMStudioContentPart {
+    Text { text: String },
+    ImageUrl { image_url: LMStudioImageUrl },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioImageUrl {
+    pub url: String, // data:<mime>;base64,<data>
+}
+
+// Asks the server for a final chunk carrying token usage
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioStreamOptions {
+    pub include_usage: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LMStudioResponse {
+    #[serde(default)]
     pub id: String,
+    #[serde(default)]
     pub object: String,
+    #[serde(default)]
     pub created: u64,
+    #[serde(default)]
     pub model: String,
+    #[serde(default)]
     pub choices: Vec<LMStudioChoice>,
+    pub usage: Option<LMStudioUsage>,
+    pub error: Option<LMStudioError>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioUsage {
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioError {
+    pub message: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LMStudioChoice {
     pub index: u32,
-    pub delta: Option<ChatMessage>,
+    pub delta: Option<LMStudioDelta>,
     pub finish_reason: Option<String>,
 }
+
+// Streaming deltas only carry the fields that changed, so both are optional
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioDelta {
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<LMStudioToolCallDelta>>,
+}
+
+// Tool calls stream in pieces: the first delta for an index carries the ID and
+// name, later ones append to the JSON arguments
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioToolCallDelta {
+    pub index: usize,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: Option<LMStudioFunctionDelta>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LMStudioFunctionDelta {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+// Model listings (Ollama /api/tags)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaTagsResponse {
+    pub models: Vec<OllamaModelTag>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaModelTag {
+    pub name: String,
+    #[serde(default)]
+    pub modified_at: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub digest: Option<String>,
+    #[serde(default)]
+    pub details: Option<OllamaModelDetails>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OllamaModelDetails {
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub family: Option<String>,
+    #[serde(default)]
+    pub parameter_size: Option<String>,
+    #[serde(default)]
+    pub quantization_level: Option<String>,
+}
+
+// Model inspection and removal (Ollama /api/show, /api/delete)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaModelRequest {
+    pub model: String,
+}
+
+// Ollama /api/embed; `input` may hold many texts, embedded in one call
+#[derive(Debug, Serialize)]
+pub struct OllamaEmbedRequest {
+    pub model: String,
+    pub input: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaEmbedResponse {
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+// Ollama /api/generate, used for fill-in-the-middle completions. With `suffix`
+// Ollama applies the model's insert template; with `raw` the prompt is sent as is.
+#[derive(Debug, Serialize)]
+pub struct OllamaGenerateRequest {
+    pub model: String,
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub raw: bool,
+    pub stream: bool,
+    pub options: OllamaOptions,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaGenerateResponse {
+    #[serde(default)]
+    pub response: String,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaVersionResponse {
+    pub version: String,
+}
+
+// Models currently loaded into memory (Ollama /api/ps)
+#[derive(Debug, Deserialize)]
+pub struct OllamaPsResponse {
+    #[serde(default)]
+    pub models: Vec<OllamaRunningModel>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OllamaRunningModel {
+    pub name: String,
+    #[serde(default)]
+    pub size: Option<u64>,
+    // Bytes held in GPU memory; the rest of `size` is offloaded to the CPU
+    #[serde(default)]
+    pub size_vram: Option<u64>,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub context_length: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaShowResponse {
+    #[serde(default)]
+    pub license: Option<String>,
+    #[serde(default)]
+    pub parameters: Option<String>,
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub details: Option<OllamaModelDetails>,
+    // Architecture-prefixed keys such as "llama.context_length"
+    #[serde(default)]
+    pub model_info: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+// Model pulls (Ollama /api/pull), streamed as one JSON object per line
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OllamaPullRequest {
+    pub model: String,
+    pub stream: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OllamaPullResponse {
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub digest: Option<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub completed: Option<u64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+// OpenAI-compatible /v1/models
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenAIModelsResponse {
+    pub data: Vec<OpenAIModel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenAIModel {
+    pub id: String,
+    #[serde(default)]
+    pub owned_by: Option<String>,
+    // Not part of the OpenAI schema, but OpenRouter reports it per model
+    #[serde(default)]
+    pub context_length: Option<u32>,
+}
+
+// What the frontend gets back from list_models / show_model
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ModelInfo {
+    pub name: String,
+    pub size: Option<u64>,
+    pub family: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization: Option<String>,
+    pub modified_at: Option<String>,
+}
+
+impl ModelInfo {
+    pub fn named(name: String) -> Self {
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModelDetails {
+    pub name: String,
+    pub family: Option<String>,
+    pub format: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization: Option<String>,
+    pub context_length: Option<u64>,
+    pub parameters: Option<String>,
+    pub template: Option<String>,
+    pub license: Option<String>,
+}
@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// THE CONVERSATIONAL PROTOCOL - Allow natural conversation with code generation
+const DEFAULT_SYSTEM_PROMPT: &str = "You are a helpful AI coding assistant. You can chat naturally with users and help them create code in ANY programming language (Python, JavaScript, TypeScript, Rust, Go, Java, C++, HTML/CSS, React, Vue, etc.). 
+
+IMPORTANT: When users ask you to create, build, or make something, you MUST provide the complete working code directly in markdown code blocks. Do NOT just give instructions or explanations - write the actual code.
+
+Example:
+User: 'Create a Python function to calculate fibonacci'
+You: 'Here's a Python function for fibonacci:
+```python
+def fibonacci(n):
+    if n <= 1:
+        return n
+    return fibonacci(n-1) + fibonacci(n-2)
+```'
+
+Be friendly and conversational, but always provide working code when requested.";
+
+// Asks for search/replace blocks that `edits::apply_edits` can apply, instead of whole files
+const EDIT_SYSTEM_PROMPT: &str = "You are an AI coding assistant working on the {{project_name}} project.
+
+When you change existing files, do NOT repeat whole files. Describe every change as a search/replace block:
+
+path/to/file.ext
+<<<<<<< SEARCH
+the exact lines currently in the file
+=======
+the lines that replace them
+>>>>>>> REPLACE
+
+Rules:
+- Paths are relative to the project root.
+- The SEARCH section must match the file exactly, including indentation, and must be unique in the file. Include a few surrounding lines if needed.
+- Use a separate block for each change. Several blocks may edit the same file.
+- To create a new file, leave the SEARCH section empty and put the whole file in the REPLACE section.
+
+Explain briefly what you changed and why.";
+
+const DEFAULT_PRESET: &str = "default";
+
+// Presets that ship with the app; saved presets with the same name shadow them
+const BUILTIN_PRESETS: &[(&str, &str)] = &[
+    (DEFAULT_PRESET, DEFAULT_SYSTEM_PROMPT),
+    ("edit", EDIT_SYSTEM_PROMPT),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SystemPromptPreset {
+    pub name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SystemPromptEntry {
+    pub name: String,
+    pub content: String,
+    pub scope: String, // "builtin", "global", "project"
+}
+
+// Which preset to use and the editor state its template variables are filled from
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PromptContext {
+    pub preset: Option<String>,
+    pub project_path: Option<String>,
+    pub file_path: Option<String>,
+    pub language: Option<String>,
+}
+
+fn get_global_prompts_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or(PathBuf::from("."))
+        .join("vibe-studio")
+        .join("prompts.json")
+}
+
+fn get_project_prompts_path(project_path: &str) -> PathBuf {
+    Path::new(project_path)
+        .join(".vibe-studio")
+        .join("prompts.json")
+}
+
+// A missing file has no presets; one that doesn't parse is an error rather than
+// an empty list, so saving a preset never overwrites the user's file
+fn load_presets(path: &Path) -> Result<Vec<SystemPromptPreset>, String> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Invalid system prompts file {}: {}", path.display(), e))
+}
+
+fn save_presets(path: &Path, presets: &[SystemPromptPreset]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(presets).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+fn presets_path(project_path: Option<&str>) -> PathBuf {
+    match project_path {
+        Some(project) => get_project_prompts_path(project),
+        None => get_global_prompts_path(),
+    }
+}
+
+fn find_preset(path: &Path, name: &str) -> Result<Option<String>, String> {
+    Ok(load_presets(path)?
+        .into_iter()
+        .find(|p| p.name == name)
+        .map(|p| p.content))
+}
+
+fn language_for_path(path: &str) -> Option<&'static str> {
+    let ext = Path::new(path).extension()?.to_str()?.to_lowercase();
+    let language = match ext.as_str() {
+        "rs" => "Rust",
+        "ts" | "tsx" => "TypeScript",
+        "js" | "jsx" | "mjs" => "JavaScript",
+        "py" => "Python",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" => "Kotlin",
+        "c" | "h" => "C",
+        "cpp" | "cc" | "hpp" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "swift" => "Swift",
+        "html" => "HTML",
+        "css" | "scss" => "CSS",
+        "vue" => "Vue",
+        "json" => "JSON",
+        "md" => "Markdown",
+        _ => return None,
+    };
+    Some(language)
+}
+
+fn render_template(template: &str, context: &PromptContext) -> String {
+    let project_name = context
+        .project_path
+        .as_deref()
+        .and_then(|p| Path::new(p).file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_path = context.file_path.clone().unwrap_or_default();
+    let language = context
+        .language
+        .clone()
+        .or_else(|| {
+            context
+                .file_path
+                .as_deref()
+                .and_then(language_for_path)
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+
+    template
+        .replace("{{project_name}}", &project_name)
+        .replace(
+            "{{project_path}}",
+            context.project_path.as_deref().unwrap_or(""),
+        )
+        .replace("{{file_path}}", &file_path)
+        .replace("{{language}}", &language)
+}
+
+/// Picks the requested preset (project presets shadow global ones) and fills in its variables.
+pub fn resolve_system_prompt(context: &PromptContext) -> Result<String, String> {
+    let name = context.preset.as_deref().unwrap_or(DEFAULT_PRESET);
+
+    let project_preset = match context.project_path.as_deref() {
+        Some(project) => find_preset(&get_project_prompts_path(project), name)?,
+        None => None,
+    };
+    let preset = match project_preset {
+        Some(content) => Some(content),
+        None => find_preset(&get_global_prompts_path(), name)?,
+    };
+
+    let template = match preset {
+        Some(content) => content,
+        None => BUILTIN_PRESETS
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, content)| content.to_string())
+            .ok_or_else(|| format!("Unknown system prompt preset: {}", name))?,
+    };
+
+    Ok(render_template(&template, context))
+}
+
+#[tauri::command]
+pub async fn list_system_prompts(
+    project_path: Option<String>,
+) -> Result<Vec<SystemPromptEntry>, String> {
+    let mut entries: Vec<SystemPromptEntry> = BUILTIN_PRESETS
+        .iter()
+        .map(|(name, content)| SystemPromptEntry {
+            name: name.to_string(),
+            content: content.to_string(),
+            scope: "builtin".to_string(),
+        })
+        .collect();
+
+    let mut add = |presets: Vec<SystemPromptPreset>, scope: &str| {
+        for preset in presets {
+            // Later scopes shadow earlier ones with the same name
+            entries.retain(|e| e.name != preset.name);
+            entries.push(SystemPromptEntry {
+                name: preset.name,
+                content: preset.content,
+                scope: scope.to_string(),
+            });
+        }
+    };
+
+    add(load_presets(&get_global_prompts_path())?, "global");
+    if let Some(project) = project_path.as_deref() {
+        add(load_presets(&get_project_prompts_path(project))?, "project");
+    }
+
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn save_system_prompt(
+    name: String,
+    content: String,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name must not be empty".to_string());
+    }
+
+    let path = presets_path(project_path.as_deref());
+    let mut presets = load_presets(&path)?;
+    match presets.iter_mut().find(|p| p.name == name) {
+        Some(existing) => existing.content = content,
+        None => presets.push(SystemPromptPreset { name, content }),
+    }
+    save_presets(&path, &presets)
+}
+
+#[tauri::command]
+pub async fn delete_system_prompt(
+    name: String,
+    project_path: Option<String>,
+) -> Result<(), String> {
+    let path = presets_path(project_path.as_deref());
+    let mut presets = load_presets(&path)?;
+    let before = presets.len();
+    presets.retain(|p| p.name != name);
+
+    if presets.len() == before {
+        return Err(format!("Unknown system prompt preset: {}", name));
+    }
+    save_presets(&path, &presets)
+}
@@ -0,0 +1,195 @@
+use super::{
+    api_error, connect_error, read_sse_data, stream_ended_early, with_headers, ChatRequest,
+    GenerationSummary, LlmProvider, ProviderError,
+};
+use crate::config::ProviderEndpoint;
+use crate::keychain;
+use crate::models::anthropic::{AnthropicMessage, AnthropicRequest, AnthropicStreamEvent};
+use crate::models::ollama::{ModelInfo, OpenAIModelsResponse, Role};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+// Every current Claude model has a 200k token window
+const CONTEXT_LENGTH: u32 = 200_000;
+
+pub struct AnthropicProvider {
+    base_url: String,
+    endpoint: ProviderEndpoint,
+}
+
+impl AnthropicProvider {
+    pub fn new(endpoint: ProviderEndpoint) -> Self {
+        Self {
+            base_url: endpoint.base_url.trim_end_matches('/').to_string(),
+            endpoint,
+        }
+    }
+
+    fn authorize(&self, builder: RequestBuilder) -> Result<RequestBuilder, String> {
+        let key = keychain::require_api_key("anthropic", "Anthropic")?;
+        Ok(with_headers(builder, &self.endpoint)
+            .header("x-api-key", key)
+            .header("anthropic-version", ANTHROPIC_VERSION))
+    }
+}
+
+// Map Anthropic's stop reasons onto the OpenAI-style names the frontend sees
+fn normalize_stop_reason(reason: &str) -> String {
+    match reason {
+        "end_turn" | "stop_sequence" => "stop".to_string(),
+        "max_tokens" => "length".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "Anthropic"
+    }
+
+    async fn stream_chat(
+        &self,
+        client: &Client,
+        request: &ChatRequest,
+        on_token: &mut (dyn FnMut(String) + Send),
+    ) -> Result<GenerationSummary, ProviderError> {
+        if request.messages.iter().any(|m| !m.images().is_empty()) {
+            return Err("Image input isn't supported for Anthropic yet".into());
+        }
+        if !request.tools.is_empty() {
+            return Err("Tool calling isn't supported for Anthropic yet".into());
+        }
+
+        let url = format!("{}/v1/messages", self.base_url);
+
+        // Anthropic takes the system prompt as a top-level field, not as a message
+        let system: Vec<String> = request
+            .messages
+            .iter()
+            .filter(|m| m.role == Role::System)
+            .map(|m| m.text())
+            .collect();
+        let messages = request
+            .messages
+            .iter()
+            .filter(|m| m.role != Role::System)
+            .map(|m| AnthropicMessage {
+                // Anthropic only knows user and assistant turns
+                role: if m.role == Role::Assistant {
+                    "assistant"
+                } else {
+                    "user"
+                }
+                .to_string(),
+                content: m.text(),
+            })
+            .collect();
+
+        let body = AnthropicRequest {
+            model: request.model.clone(),
+            max_tokens: request.options.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            system: (!system.is_empty()).then(|| system.join("\n\n")),
+            messages,
+            stream: true,
+            // Anthropic only accepts temperatures up to 1.0
+            temperature: request.options.temperature().min(1.0),
+            top_p: request.options.top_p,
+            stop_sequences: request.options.stop.clone(),
+        };
+
+        let response = self
+            .authorize(client.post(&url))?
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| connect_error("Anthropic", &e))?;
+
+        if !response.status().is_success() {
+            return Err(api_error("Anthropic", response.status()));
+        }
+
+        let mut summary = GenerationSummary::default();
+        let mut finished = false;
+
+        read_sse_data(response, "Anthropic", |data| {
+            let Ok(event) = serde_json::from_str::<AnthropicStreamEvent>(data) else {
+                return Ok(true);
+            };
+
+            match event.event_type.as_str() {
+                "message_start" => {
+                    summary.prompt_tokens = event
+                        .message
+                        .and_then(|m| m.usage)
+                        .and_then(|u| u.input_tokens);
+                    Ok(true)
+                }
+                "content_block_delta" => {
+                    if let Some(text) = event.delta.and_then(|d| d.text) {
+                        on_token(text);
+                    }
+                    Ok(true)
+                }
+                "message_delta" => {
+                    if let Some(reason) = event.delta.and_then(|d| d.stop_reason) {
+                        summary.finish_reason = Some(normalize_stop_reason(&reason));
+                    }
+                    if let Some(tokens) = event.usage.and_then(|u| u.output_tokens) {
+                        summary.completion_tokens = Some(tokens);
+                    }
+                    Ok(true)
+                }
+                "message_stop" => {
+                    finished = true;
+                    Ok(false)
+                }
+                "error" => Err(match event.error {
+                    Some(err) => format!("Anthropic API Error: {}", err.message),
+                    None => "Anthropic API Error".to_string(),
+                }),
+                _ => Ok(true),
+            }
+        })
+        .await?;
+
+        if !finished {
+            return Err(stream_ended_early("Anthropic"));
+        }
+        Ok(summary)
+    }
+
+    async fn context_length(&self, _client: &Client, _model: &str) -> Option<u32> {
+        Some(CONTEXT_LENGTH)
+    }
+
+    async fn list_models(&self, client: &Client) -> Result<Vec<ModelInfo>, String> {
+        let url = format!("{}/v1/models", self.base_url);
+        let response = self
+            .authorize(client.get(&url))?
+            .send()
+            .await
+            .map_err(|e| connect_error("Anthropic", &e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Anthropic API Error: {}", response.status()));
+        }
+
+        let models = response
+            .json::<OpenAIModelsResponse>()
+            .await
+            .map_err(|e| format!("Invalid Anthropic response: {}", e))?;
+
+        Ok(models
+            .data
+            .into_iter()
+            .map(|m| ModelInfo::named(m.id))
+            .collect())
+    }
+
+    async fn health_check(&self, client: &Client) -> Result<(), String> {
+        self.list_models(client).await.map(|_| ())
+    }
+}
@@ -0,0 +1,194 @@
+use super::{
+    api_error, connect_error, read_sse_data, stream_ended_early, with_headers, ChatRequest,
+    GenerationSummary, LlmProvider, ProviderError,
+};
+use crate::config::ProviderEndpoint;
+use crate::keychain;
+use crate::models::gemini::{
+    GeminiContent, GeminiGenerationConfig, GeminiModel, GeminiModelsResponse, GeminiPart,
+    GeminiRequest, GeminiResponse,
+};
+use crate::models::ollama::{ModelInfo, Role};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+
+pub struct GeminiProvider {
+    base_url: String,
+    endpoint: ProviderEndpoint,
+}
+
+impl GeminiProvider {
+    pub fn new(endpoint: ProviderEndpoint) -> Self {
+        Self {
+            base_url: endpoint.base_url.trim_end_matches('/').to_string(),
+            endpoint,
+        }
+    }
+
+    fn authorize(&self, builder: RequestBuilder) -> Result<RequestBuilder, String> {
+        let key = keychain::require_api_key("gemini", "Google Gemini")?;
+        Ok(with_headers(builder, &self.endpoint).header("x-goog-api-key", key))
+    }
+}
+
+fn text_content(role: Option<&str>, text: &str) -> GeminiContent {
+    GeminiContent {
+        role: role.map(str::to_string),
+        parts: vec![GeminiPart {
+            text: text.to_string(),
+        }],
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GeminiProvider {
+    fn name(&self) -> &str {
+        "Google Gemini"
+    }
+
+    async fn stream_chat(
+        &self,
+        client: &Client,
+        request: &ChatRequest,
+        on_token: &mut (dyn FnMut(String) + Send),
+    ) -> Result<GenerationSummary, ProviderError> {
+        if request.messages.iter().any(|m| !m.images().is_empty()) {
+            return Err("Image input isn't supported for Google Gemini yet".into());
+        }
+        if !request.tools.is_empty() {
+            return Err("Tool calling isn't supported for Google Gemini yet".into());
+        }
+
+        let url = format!(
+            "{}/v1beta/models/{}:streamGenerateContent?alt=sse",
+            self.base_url, request.model
+        );
+
+        // Gemini calls the assistant "model" and keeps the system prompt separate
+        let system: Vec<String> = request
+            .messages
+            .iter()
+            .filter(|m| m.role == Role::System)
+            .map(|m| m.text())
+            .collect();
+        let contents = request
+            .messages
+            .iter()
+            .filter(|m| m.role != Role::System)
+            .map(|m| {
+                let role = if m.role == Role::Assistant {
+                    "model"
+                } else {
+                    "user"
+                };
+                text_content(Some(role), &m.text())
+            })
+            .collect();
+
+        let body = GeminiRequest {
+            contents,
+            system_instruction: (!system.is_empty())
+                .then(|| text_content(None, &system.join("\n\n"))),
+            generation_config: GeminiGenerationConfig {
+                temperature: request.options.temperature(),
+                top_p: request.options.top_p,
+                max_output_tokens: request.options.max_tokens,
+                stop_sequences: request.options.stop.clone(),
+            },
+        };
+
+        let response = self
+            .authorize(client.post(&url))?
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| connect_error("Google Gemini", &e))?;
+
+        if !response.status().is_success() {
+            return Err(api_error("Google Gemini", response.status()));
+        }
+
+        let mut summary = GenerationSummary::default();
+
+        // Gemini has no end-of-stream event; the last chunk carries the finish reason
+        read_sse_data(response, "Google Gemini", |data| {
+            let Ok(res) = serde_json::from_str::<GeminiResponse>(data) else {
+                return Ok(true);
+            };
+
+            if let Some(error) = res.error {
+                return Err(format!("Google Gemini API Error: {}", error.message));
+            }
+
+            if let Some(candidate) = res.candidates.into_iter().next() {
+                for part in candidate.content.map(|c| c.parts).unwrap_or_default() {
+                    if !part.text.is_empty() {
+                        on_token(part.text);
+                    }
+                }
+                if let Some(reason) = candidate.finish_reason {
+                    summary.finish_reason = Some(match reason.as_str() {
+                        "STOP" => "stop".to_string(),
+                        "MAX_TOKENS" => "length".to_string(),
+                        other => other.to_lowercase(),
+                    });
+                }
+            }
+
+            if let Some(usage) = res.usage_metadata {
+                summary.prompt_tokens = usage.prompt_token_count;
+                summary.completion_tokens = usage.candidates_token_count;
+            }
+
+            Ok(true)
+        })
+        .await?;
+
+        if summary.finish_reason.is_none() {
+            return Err(stream_ended_early("Google Gemini"));
+        }
+        Ok(summary)
+    }
+
+    async fn context_length(&self, client: &Client, model: &str) -> Option<u32> {
+        let url = format!("{}/v1beta/models/{}", self.base_url, model);
+        let response = self.authorize(client.get(&url)).ok()?.send().await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+
+        response
+            .json::<GeminiModel>()
+            .await
+            .ok()
+            .and_then(|m| m.input_token_limit)
+    }
+
+    async fn list_models(&self, client: &Client) -> Result<Vec<ModelInfo>, String> {
+        let url = format!("{}/v1beta/models", self.base_url);
+        let response = self
+            .authorize(client.get(&url))?
+            .send()
+            .await
+            .map_err(|e| connect_error("Google Gemini", &e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Google Gemini API Error: {}", response.status()));
+        }
+
+        let models = response
+            .json::<GeminiModelsResponse>()
+            .await
+            .map_err(|e| format!("Invalid Google Gemini response: {}", e))?;
+
+        Ok(models
+            .models
+            .into_iter()
+            .map(|m| ModelInfo::named(m.name.trim_start_matches("models/").to_string()))
+            .collect())
+    }
+
+    async fn health_check(&self, client: &Client) -> Result<(), String> {
+        self.list_models(client).await.map(|_| ())
+    }
+}
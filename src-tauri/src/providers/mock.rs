@@ -0,0 +1,206 @@
+use super::{ChatRequest, GenerationSummary, LlmProvider, ProviderError};
+use crate::models::ollama::{ModelInfo, Role, ToolCall};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// Built-in model that needs no transcript: replies with the last user message
+pub const ECHO_MODEL: &str = "echo";
+const MOCK_CONTEXT_LENGTH: u32 = 8192;
+
+/// A recorded conversation to replay. Turn N answers the request that already
+/// holds N assistant messages, so agent loops and follow-ups advance through it.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MockTranscript {
+    pub turns: Vec<MockTurn>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MockTurn {
+    // Streamed in order; `text` is a shorthand that gets split on spaces
+    #[serde(default)]
+    pub tokens: Vec<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    // Pause before each token, to exercise cancellation and timing
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    #[serde(default)]
+    pub prompt_tokens: Option<u32>,
+    // Fails the stream after the tokens above have been sent
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl MockTurn {
+    pub fn tokens(&self) -> Vec<String> {
+        match &self.text {
+            Some(text) => text.split_inclusive(' ').map(str::to_string).collect(),
+            None => self.tokens.clone(),
+        }
+    }
+
+    pub fn finish_reason(&self) -> String {
+        self.finish_reason.clone().unwrap_or_else(|| {
+            if self.tool_calls.is_empty() {
+                "stop".to_string()
+            } else {
+                "tool_calls".to_string()
+            }
+        })
+    }
+
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay_ms)
+    }
+}
+
+fn transcripts_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or(PathBuf::from("."))
+        .join("vibe-studio")
+        .join("transcripts")
+}
+
+/// Loads `<config dir>/vibe-studio/transcripts/<name>.json`.
+pub fn load_transcript(name: &str) -> Result<MockTranscript, String> {
+    if name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid transcript name: {}", name));
+    }
+    let path = transcripts_dir().join(format!("{}.json", name));
+    let json = fs::read_to_string(&path)
+        .map_err(|e| format!("No mock transcript at {}: {}", path.display(), e))?;
+    serde_json::from_str(&json).map_err(|e| format!("Invalid transcript {}: {}", name, e))
+}
+
+/// The turn that answers a conversation with `assistant_messages` replies so far.
+pub fn transcript_turn(
+    transcript: &MockTranscript,
+    name: &str,
+    assistant_messages: usize,
+) -> Result<MockTurn, String> {
+    transcript
+        .turns
+        .get(assistant_messages)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "Transcript {} has no turn {} ({} recorded)",
+                name,
+                assistant_messages + 1,
+                transcript.turns.len()
+            )
+        })
+}
+
+fn echo_turn(request: &ChatRequest) -> MockTurn {
+    let prompt = request
+        .messages
+        .iter()
+        .rev()
+        .find(|m| m.role == Role::User)
+        .map(|m| m.text())
+        .unwrap_or_default();
+    MockTurn {
+        text: Some(format!("You said: {}", prompt)),
+        ..Default::default()
+    }
+}
+
+/// Offline provider that replays transcripts, for trying the app and testing
+/// streaming, cancellation, errors and tool calls without any model installed.
+#[derive(Default)]
+pub struct MockProvider {
+    // Replayed for every model but "echo" instead of loading `<model>.json`
+    transcript: Option<MockTranscript>,
+}
+
+impl MockProvider {
+    #[cfg(test)]
+    pub fn replaying(transcript: MockTranscript) -> Self {
+        Self {
+            transcript: Some(transcript),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for MockProvider {
+    fn name(&self) -> &str {
+        "Mock"
+    }
+
+    async fn stream_chat(
+        &self,
+        _client: &Client,
+        request: &ChatRequest,
+        on_token: &mut (dyn FnMut(String) + Send),
+    ) -> Result<GenerationSummary, ProviderError> {
+        let turn = if request.model == ECHO_MODEL {
+            echo_turn(request)
+        } else {
+            let assistant_messages = request
+                .messages
+                .iter()
+                .filter(|m| m.role == Role::Assistant)
+                .count();
+            match &self.transcript {
+                Some(transcript) => {
+                    transcript_turn(transcript, &request.model, assistant_messages)?
+                }
+                None => {
+                    let transcript = load_transcript(&request.model)?;
+                    transcript_turn(&transcript, &request.model, assistant_messages)?
+                }
+            }
+        };
+
+        let tokens = turn.tokens();
+        for token in &tokens {
+            tokio::time::sleep(turn.delay()).await;
+            on_token(token.clone());
+        }
+        if let Some(error) = turn.error.clone() {
+            return Err(format!("Mock error: {}", error).into());
+        }
+
+        Ok(GenerationSummary {
+            finish_reason: Some(turn.finish_reason()),
+            prompt_tokens: turn.prompt_tokens,
+            completion_tokens: Some(tokens.len() as u32),
+            tool_calls: turn.tool_calls,
+            ..Default::default()
+        })
+    }
+
+    async fn context_length(&self, _client: &Client, _model: &str) -> Option<u32> {
+        Some(MOCK_CONTEXT_LENGTH)
+    }
+
+    async fn list_models(&self, _client: &Client) -> Result<Vec<ModelInfo>, String> {
+        let mut models = vec![ModelInfo::named(ECHO_MODEL.to_string())];
+        if let Ok(entries) = fs::read_dir(transcripts_dir()) {
+            for entry in entries.flatten() {
+                if let Some(name) = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|n| n.strip_suffix(".json"))
+                {
+                    models.push(ModelInfo::named(name.to_string()));
+                }
+            }
+        }
+        Ok(models)
+    }
+
+    async fn health_check(&self, _client: &Client) -> Result<(), String> {
+        Ok(())
+    }
+}
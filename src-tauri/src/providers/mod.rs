@@ -0,0 +1,356 @@
+use crate::config::{ProviderConfig, ProviderEndpoint};
+use crate::models::ollama::{ChatMessage, ModelInfo, ToolCall, ToolDefinition};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+pub mod anthropic;
+pub mod gemini;
+// Only for development and tests; release builds don't offer it
+#[cfg(any(test, debug_assertions))]
+pub mod mock;
+pub mod ollama;
+pub mod openai;
+mod options;
+pub mod stream;
+
+pub use options::GenerationOptions;
+pub(crate) use stream::{read_ndjson_lines, read_sse_data};
+
+// Reported by `check_providers`; fields stay empty when the backend doesn't say
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct BackendInfo {
+    pub version: Option<String>,
+    pub models_available: Option<usize>,
+    pub loaded_models: Vec<LoadedModel>,
+}
+
+// A model currently held in memory (Ollama /api/ps)
+#[derive(Debug, Serialize, Clone)]
+pub struct LoadedModel {
+    pub name: String,
+    pub size: Option<u64>,
+    pub size_vram: Option<u64>,
+    pub processor: Option<String>, // "100% GPU", "48%/52% CPU/GPU", "100% CPU"
+    pub context_length: Option<u32>,
+    pub expires_at: Option<String>,
+}
+
+// Reported with `generate-finished`; fields stay empty when the backend doesn't say
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GenerationSummary {
+    pub finish_reason: Option<String>, // "stop", "length", ...
+    pub prompt_tokens: Option<u32>,
+    pub completion_tokens: Option<u32>,
+    pub eval_duration_ms: Option<u64>,
+    pub tokens_per_second: Option<f64>,
+    // Tools the model asked to run; reported through their own events
+    #[serde(skip)]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+impl GenerationSummary {
+    /// Fills in timing from the wall clock when the backend didn't report its own.
+    pub fn with_timing(mut self, elapsed: Duration) -> Self {
+        let eval_ms = *self
+            .eval_duration_ms
+            .get_or_insert(elapsed.as_millis() as u64);
+
+        if self.tokens_per_second.is_none() && eval_ms > 0 {
+            self.tokens_per_second = self
+                .completion_tokens
+                .map(|tokens| tokens as f64 * 1000.0 / eval_ms as f64);
+        }
+        self
+    }
+}
+
+// What went wrong with a chat request, so fallback can tell a flaky backend
+// from one that will never accept the request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Connect,     // refused, timed out or dropped mid-response
+    Status(u16), // the backend answered with an HTTP error
+    Other,       // missing API key, unknown model, invalid response, ...
+}
+
+#[derive(Debug, Clone)]
+pub struct ProviderError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl ProviderError {
+    /// Worth trying again on the same provider: it was unreachable or had a
+    /// server-side failure.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind {
+            ErrorKind::Connect => true,
+            ErrorKind::Status(status) => status >= 500,
+            ErrorKind::Other => false,
+        }
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<String> for ProviderError {
+    fn from(message: String) -> Self {
+        Self {
+            kind: ErrorKind::Other,
+            message,
+        }
+    }
+}
+
+impl From<&str> for ProviderError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<ProviderError> for String {
+    fn from(error: ProviderError) -> Self {
+        error.message
+    }
+}
+
+// Backend-agnostic chat request handed to every provider
+#[derive(Debug, Clone)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub options: GenerationOptions,
+    // Offered to the model for function calling; empty for plain chat
+    pub tools: Vec<ToolDefinition>,
+}
+
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Display name used in error messages ("Failed to connect to Ollama").
+    fn name(&self) -> &str;
+
+    /// Streams the assistant reply, calling `on_token` for every content delta.
+    async fn stream_chat(
+        &self,
+        client: &Client,
+        request: &ChatRequest,
+        on_token: &mut (dyn FnMut(String) + Send),
+    ) -> Result<GenerationSummary, ProviderError>;
+
+    /// Runs the chat to completion and returns the full assistant text.
+    async fn chat(&self, client: &Client, request: &ChatRequest) -> Result<String, String> {
+        let mut text = String::new();
+        self.stream_chat(client, request, &mut |token| text.push_str(&token))
+            .await?;
+        Ok(text)
+    }
+
+    /// Context window of `model` in tokens, if the backend can tell.
+    async fn context_length(&self, _client: &Client, _model: &str) -> Option<u32> {
+        None
+    }
+
+    /// Returns the models the backend can serve, with whatever metadata it reports.
+    async fn list_models(&self, client: &Client) -> Result<Vec<ModelInfo>, String>;
+
+    /// Succeeds if the backend is reachable and answering requests.
+    async fn health_check(&self, client: &Client) -> Result<(), String>;
+
+    /// What the backend reports about itself, for the provider dashboard.
+    async fn backend_info(&self, client: &Client) -> Result<BackendInfo, String> {
+        let models = self.list_models(client).await?;
+        Ok(BackendInfo {
+            models_available: Some(models.len()),
+            ..Default::default()
+        })
+    }
+}
+
+/// Describes a failed request as, e.g., "Failed to connect to Ollama at <url>:
+/// Connection refused (os error 111)". reqwest's own message stops at "error
+/// sending request", so this digs out the underlying cause.
+pub(crate) fn connect_error(name: &str, error: &reqwest::Error) -> ProviderError {
+    let reason = if error.is_timeout() {
+        "timed out".to_string()
+    } else {
+        let mut cause: &dyn std::error::Error = error;
+        while let Some(source) = cause.source() {
+            cause = source;
+        }
+        cause.to_string()
+    };
+    let target = match error.url() {
+        Some(url) => format!("{} at {}", name, url),
+        None => name.to_string(),
+    };
+
+    // A malformed URL or request fails the same way on every attempt
+    let rejected =
+        error.is_builder() || (error.is_request() && !error.is_connect() && !error.is_timeout());
+    if rejected {
+        return ProviderError {
+            kind: ErrorKind::Other,
+            message: format!("Request to {} failed: {}", target, reason),
+        };
+    }
+    ProviderError {
+        kind: ErrorKind::Connect,
+        message: format!("Failed to connect to {}: {}", target, reason),
+    }
+}
+
+/// For a non-success HTTP status, e.g. "Ollama API Error: 503 Service Unavailable".
+pub(crate) fn api_error(name: &str, status: reqwest::StatusCode) -> ProviderError {
+    ProviderError {
+        kind: ErrorKind::Status(status.as_u16()),
+        message: format!("{} API Error: {}", name, status),
+    }
+}
+
+/// For a response that stopped before the backend's end-of-stream marker, which
+/// means the connection dropped rather than the generation finishing.
+pub(crate) fn stream_ended_early(name: &str) -> ProviderError {
+    ProviderError {
+        kind: ErrorKind::Connect,
+        message: format!("{} stream ended before completion", name),
+    }
+}
+
+/// Adds the endpoint's configured extra headers to an outgoing request.
+pub(crate) fn with_headers(
+    mut builder: RequestBuilder,
+    endpoint: &ProviderEndpoint,
+) -> RequestBuilder {
+    for (name, value) in &endpoint.headers {
+        builder = builder.header(name, value);
+    }
+    builder
+}
+
+pub struct ProviderRegistry {
+    providers: RwLock<HashMap<String, Arc<dyn LlmProvider>>>,
+}
+
+impl ProviderRegistry {
+    pub fn new() -> Self {
+        Self {
+            providers: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config(config: &ProviderConfig) -> Self {
+        let registry = Self::new();
+        registry.reload(config);
+        registry
+    }
+
+    pub fn register(&self, id: &str, provider: Arc<dyn LlmProvider>) {
+        self.providers
+            .write()
+            .unwrap()
+            .insert(id.to_string(), provider);
+    }
+
+    /// Every registered provider, sorted by ID.
+    pub fn all(&self) -> Vec<(String, Arc<dyn LlmProvider>)> {
+        let mut providers: Vec<_> = self
+            .providers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(id, provider)| (id.clone(), provider.clone()))
+            .collect();
+        providers.sort_by(|a, b| a.0.cmp(&b.0));
+        providers
+    }
+
+    pub fn get(&self, id: &str) -> Result<Arc<dyn LlmProvider>, String> {
+        self.providers
+            .read()
+            .unwrap()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown provider: {}", id))
+    }
+
+    /// Rebuilds the built-in providers against the given endpoint configuration.
+    pub fn reload(&self, config: &ProviderConfig) {
+        self.register(
+            "ollama",
+            Arc::new(ollama::OllamaProvider::new(config.endpoint("ollama"))),
+        );
+        self.register(
+            "lmstudio",
+            Arc::new(openai::OpenAICompatibleProvider::new(
+                "LM Studio",
+                config.endpoint("lmstudio"),
+            )),
+        );
+        self.register(
+            "openai",
+            Arc::new(
+                openai::OpenAICompatibleProvider::new("OpenAI", config.endpoint("openai"))
+                    .with_api_key("openai"),
+            ),
+        );
+        self.register(
+            "openrouter",
+            Arc::new(
+                openai::OpenAICompatibleProvider::new("OpenRouter", config.endpoint("openrouter"))
+                    .with_api_key("openrouter"),
+            ),
+        );
+        self.register(
+            "anthropic",
+            Arc::new(anthropic::AnthropicProvider::new(
+                config.endpoint("anthropic"),
+            )),
+        );
+        self.register(
+            "gemini",
+            Arc::new(gemini::GeminiProvider::new(config.endpoint("gemini"))),
+        );
+        #[cfg(debug_assertions)]
+        self.register("mock", Arc::new(mock::MockProvider::default()));
+    }
+}
+
+impl Default for ProviderRegistry {
+    fn default() -> Self {
+        Self::from_config(&ProviderConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn only_network_failures_count_as_connection_errors() {
+        let client = Client::new();
+
+        let refused = client.get("http://127.0.0.1:1").send().await.unwrap_err();
+        let refused = connect_error("Ollama", &refused);
+        assert_eq!(refused.kind, ErrorKind::Connect);
+        assert!(refused.is_retryable());
+
+        let malformed = client.get("not a url").send().await.unwrap_err();
+        let malformed = connect_error("Ollama", &malformed);
+        assert_eq!(malformed.kind, ErrorKind::Other);
+        assert!(!malformed.is_retryable());
+        assert!(
+            malformed.message.starts_with("Request to Ollama"),
+            "{}",
+            malformed
+        );
+    }
+}
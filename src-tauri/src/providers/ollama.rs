@@ -0,0 +1,508 @@
+use super::{
+    api_error, connect_error, read_ndjson_lines, stream_ended_early, with_headers, BackendInfo,
+    ChatRequest, GenerationSummary, LlmProvider, LoadedModel, ProviderError,
+};
+use crate::config::ProviderEndpoint;
+use crate::models::ollama::{
+    ChatMessage, FunctionTool, ModelDetails, ModelInfo, OllamaChatRequest, OllamaChatResponse,
+    OllamaEmbedRequest, OllamaEmbedResponse, OllamaFunctionCall, OllamaGenerateRequest,
+    OllamaGenerateResponse, OllamaMessage, OllamaModelRequest, OllamaOptions, OllamaPsResponse,
+    OllamaPullRequest, OllamaPullResponse, OllamaRunningModel, OllamaShowResponse,
+    OllamaTagsResponse, OllamaToolCall, OllamaVersionResponse, ToolCall,
+};
+use async_trait::async_trait;
+use reqwest::Client;
+
+// Window a model runs with when its Modelfile doesn't set one
+const DEFAULT_CONTEXT_LENGTH: u32 = 8192;
+
+pub struct OllamaProvider {
+    base_url: String,
+    endpoint: ProviderEndpoint,
+}
+
+impl OllamaProvider {
+    pub fn new(endpoint: ProviderEndpoint) -> Self {
+        Self {
+            base_url: endpoint.base_url.trim_end_matches('/').to_string(),
+            endpoint,
+        }
+    }
+
+    pub async fn show_model(&self, client: &Client, model: &str) -> Result<ModelDetails, String> {
+        let url = format!("{}/api/show", self.base_url);
+        let response = with_headers(client.post(&url), &self.endpoint)
+            .json(&OllamaModelRequest {
+                model: model.to_string(),
+            })
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Ollama API Error: {}", response.status()));
+        }
+
+        let show = response
+            .json::<OllamaShowResponse>()
+            .await
+            .map_err(|e| format!("Invalid Ollama response: {}", e))?;
+
+        let details = show.details.unwrap_or_default();
+        let context_length = show.model_info.as_ref().and_then(|info| {
+            info.iter()
+                .find(|(key, _)| key.ends_with(".context_length"))
+                .and_then(|(_, value)| value.as_u64())
+        });
+
+        Ok(ModelDetails {
+            name: model.to_string(),
+            family: details.family,
+            format: details.format,
+            parameter_size: details.parameter_size,
+            quantization: details.quantization_level,
+            context_length,
+            parameters: show.parameters,
+            template: show.template,
+            license: show.license,
+        })
+    }
+
+    /// Pulls `model`, reporting every progress line Ollama sends until it says "success".
+    pub async fn pull_model(
+        &self,
+        client: &Client,
+        model: &str,
+        on_progress: &mut (dyn FnMut(OllamaPullResponse) + Send),
+    ) -> Result<(), String> {
+        let url = format!("{}/api/pull", self.base_url);
+        let response = with_headers(client.post(&url), &self.endpoint)
+            .json(&OllamaPullRequest {
+                model: model.to_string(),
+                stream: true,
+            })
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Ollama API Error: {}", response.status()));
+        }
+
+        let mut succeeded = false;
+        read_ndjson_lines(response, "Ollama", |line| {
+            let Ok(progress) = serde_json::from_str::<OllamaPullResponse>(line) else {
+                return Ok(true);
+            };
+
+            if let Some(error) = progress.error {
+                return Err(format!("Failed to pull {}: {}", model, error));
+            }
+
+            succeeded = progress.status == "success";
+            on_progress(progress);
+            Ok(!succeeded)
+        })
+        .await?;
+
+        if succeeded {
+            return Ok(());
+        }
+        Err(format!(
+            "Pull of {} ended before Ollama reported success",
+            model
+        ))
+    }
+
+    pub async fn delete_model(&self, client: &Client, model: &str) -> Result<(), String> {
+        let url = format!("{}/api/delete", self.base_url);
+        let response = with_headers(client.delete(&url), &self.endpoint)
+            .json(&OllamaModelRequest {
+                model: model.to_string(),
+            })
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            reqwest::StatusCode::NOT_FOUND => Err(format!("Model not found: {}", model)),
+            status => Err(format!("Ollama API Error: {}", status)),
+        }
+    }
+
+    /// Runs a single non-streaming /api/generate call and returns the generated text.
+    pub async fn generate(
+        &self,
+        client: &Client,
+        request: &OllamaGenerateRequest,
+    ) -> Result<String, String> {
+        let url = format!("{}/api/generate", self.base_url);
+        let response = with_headers(client.post(&url), &self.endpoint)
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        // Failures usually come back as {"error": "..."}, which says more than the status
+        let status = response.status();
+        match response.json::<OllamaGenerateResponse>().await {
+            Ok(OllamaGenerateResponse {
+                error: Some(error), ..
+            }) => Err(format!("Ollama error: {}", error)),
+            Ok(body) if status.is_success() => Ok(body.response),
+            Ok(_) => Err(format!("Ollama API Error: {}", status)),
+            Err(_) if !status.is_success() => Err(format!("Ollama API Error: {}", status)),
+            Err(e) => Err(format!("Invalid Ollama response: {}", e)),
+        }
+    }
+
+    /// Embeds each input with `model`, returning one vector per input in order.
+    pub async fn embed(
+        &self,
+        client: &Client,
+        model: &str,
+        input: Vec<String>,
+    ) -> Result<Vec<Vec<f32>>, String> {
+        let url = format!("{}/api/embed", self.base_url);
+        let count = input.len();
+        let response = with_headers(client.post(&url), &self.endpoint)
+            .json(&OllamaEmbedRequest {
+                model: model.to_string(),
+                input,
+            })
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        match response.status() {
+            status if status.is_success() => {}
+            reqwest::StatusCode::NOT_FOUND => {
+                return Err(format!("Embedding model not found: {}", model))
+            }
+            status => return Err(format!("Ollama API Error: {}", status)),
+        }
+
+        let embeddings = response
+            .json::<OllamaEmbedResponse>()
+            .await
+            .map_err(|e| format!("Invalid Ollama response: {}", e))?
+            .embeddings;
+        if embeddings.len() != count {
+            return Err(format!(
+                "Ollama returned {} embeddings for {} inputs",
+                embeddings.len(),
+                count
+            ));
+        }
+        Ok(embeddings)
+    }
+
+    /// The server's version, e.g. "0.5.7".
+    pub async fn version(&self, client: &Client) -> Result<String, String> {
+        let url = format!("{}/api/version", self.base_url);
+        let response = with_headers(client.get(&url), &self.endpoint)
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Ollama API Error: {}", response.status()));
+        }
+
+        response
+            .json::<OllamaVersionResponse>()
+            .await
+            .map(|v| v.version)
+            .map_err(|e| format!("Invalid Ollama response: {}", e))
+    }
+
+    /// Models Ollama currently holds in memory, and where.
+    pub async fn running_models(&self, client: &Client) -> Result<Vec<OllamaRunningModel>, String> {
+        let url = format!("{}/api/ps", self.base_url);
+        let response = with_headers(client.get(&url), &self.endpoint)
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Ollama API Error: {}", response.status()));
+        }
+
+        response
+            .json::<OllamaPsResponse>()
+            .await
+            .map(|ps| ps.models)
+            .map_err(|e| format!("Invalid Ollama response: {}", e))
+    }
+}
+
+// `parameters` from /api/show is the Modelfile's PARAMETER lines, e.g. "num_ctx 4096"
+fn modelfile_num_ctx(parameters: &str) -> Option<u32> {
+    parameters.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        match (fields.next(), fields.next()) {
+            (Some("num_ctx"), Some(value)) => value.parse().ok(),
+            _ => None,
+        }
+    })
+}
+
+// Same wording as `ollama ps`: "100% GPU", "100% CPU" or "48%/52% CPU/GPU"
+fn processor_split(size: u64, size_vram: u64) -> String {
+    if size_vram == 0 {
+        return "100% CPU".to_string();
+    }
+    if size_vram >= size {
+        return "100% GPU".to_string();
+    }
+    let gpu = (size_vram as f64 / size as f64 * 100.0).round() as u64;
+    format!("{}%/{}% CPU/GPU", 100 - gpu, gpu)
+}
+
+fn loaded_model(model: OllamaRunningModel) -> LoadedModel {
+    let processor = match (model.size, model.size_vram) {
+        (Some(size), Some(size_vram)) if size > 0 => Some(processor_split(size, size_vram)),
+        _ => None,
+    };
+    LoadedModel {
+        name: model.name,
+        size: model.size,
+        size_vram: model.size_vram,
+        processor,
+        context_length: model.context_length,
+        expires_at: model.expires_at,
+    }
+}
+
+// `earlier` is the conversation before `message`
+fn ollama_message(message: &ChatMessage, earlier: &[ChatMessage]) -> OllamaMessage {
+    let images: Vec<String> = message
+        .images()
+        .iter()
+        .map(|(_, data)| data.to_string())
+        .collect();
+
+    let tool_calls: Vec<OllamaToolCall> = message
+        .tool_calls
+        .iter()
+        .map(|call| OllamaToolCall {
+            function: OllamaFunctionCall {
+                name: call.name.clone(),
+                arguments: call.arguments.clone(),
+            },
+        })
+        .collect();
+
+    // Look up the name of the call this tool message answers, nearest first in
+    // case an older history reused the ID
+    let tool_name = message.tool_call_id.as_ref().and_then(|id| {
+        earlier
+            .iter()
+            .rev()
+            .flat_map(|m| &m.tool_calls)
+            .find(|call| &call.id == id)
+            .map(|call| call.name.clone())
+    });
+
+    OllamaMessage {
+        role: message.role.as_str().to_string(),
+        content: message.text(),
+        images: (!images.is_empty()).then_some(images),
+        tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+        tool_name,
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "Ollama"
+    }
+
+    async fn stream_chat(
+        &self,
+        client: &Client,
+        request: &ChatRequest,
+        on_token: &mut (dyn FnMut(String) + Send),
+    ) -> Result<GenerationSummary, ProviderError> {
+        let url = format!("{}/api/chat", self.base_url);
+        let body = OllamaChatRequest {
+            model: request.model.clone(),
+            messages: request
+                .messages
+                .iter()
+                .enumerate()
+                .map(|(i, m)| ollama_message(m, &request.messages[..i]))
+                .collect(),
+            stream: true,
+            options: Some(OllamaOptions {
+                temperature: Some(request.options.temperature()),
+                top_p: request.options.top_p,
+                num_predict: request.options.max_tokens,
+                num_ctx: request.options.num_ctx,
+                stop: request.options.stop.clone(),
+            }),
+            tools: (!request.tools.is_empty())
+                .then(|| request.tools.iter().map(FunctionTool::from).collect()),
+        };
+
+        // Counts only: messages can carry megabytes of base64 images and project context
+        let images: usize = request.messages.iter().map(|m| m.images().len()).sum();
+        println!(
+            "📝 Sending {} messages ({} images)",
+            request.messages.len(),
+            images
+        );
+
+        let response = with_headers(client.post(&url), &self.endpoint)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        if !response.status().is_success() {
+            return Err(api_error("Ollama", response.status()));
+        }
+
+        println!("✅ Ollama responded, streaming...");
+
+        let mut summary = GenerationSummary::default();
+        let mut tool_calls = Vec::new();
+        let mut finished = false;
+        // Continue numbering from earlier calls so IDs stay unique across agent steps
+        let earlier_calls: usize = request.messages.iter().map(|m| m.tool_calls.len()).sum();
+
+        read_ndjson_lines(response, "Ollama", |line| {
+            let Ok(res) = serde_json::from_str::<OllamaChatResponse>(line) else {
+                return Ok(true);
+            };
+
+            if let Some(error) = res.error {
+                return Err(format!("Ollama error: {}", error));
+            }
+
+            if let Some(msg) = res.message {
+                // Ollama doesn't assign call IDs, so make up our own
+                for call in msg.tool_calls.unwrap_or_default() {
+                    tool_calls.push(ToolCall {
+                        id: format!("call_{}", earlier_calls + tool_calls.len()),
+                        name: call.function.name,
+                        arguments: call.function.arguments,
+                    });
+                }
+                // Tool-call and keep-alive chunks carry no text
+                if !msg.content.is_empty() {
+                    on_token(msg.content);
+                }
+            }
+
+            if res.done {
+                println!("✅ Generation finished");
+                finished = true;
+                summary = GenerationSummary {
+                    finish_reason: res.done_reason,
+                    prompt_tokens: res.prompt_eval_count,
+                    completion_tokens: res.eval_count,
+                    eval_duration_ms: res.eval_duration.map(|ns| ns / 1_000_000),
+                    tokens_per_second: match (res.eval_count, res.eval_duration) {
+                        (Some(count), Some(ns)) if ns > 0 => {
+                            Some(count as f64 / (ns as f64 / 1_000_000_000.0))
+                        }
+                        _ => None,
+                    },
+                    tool_calls: std::mem::take(&mut tool_calls),
+                };
+                return Ok(false);
+            }
+
+            Ok(true)
+        })
+        .await?;
+
+        if !finished {
+            return Err(stream_ended_early("Ollama"));
+        }
+        Ok(summary)
+    }
+
+    async fn context_length(&self, client: &Client, model: &str) -> Option<u32> {
+        let details = self.show_model(client, model).await.ok()?;
+        // A Modelfile `num_ctx` is the window the model is meant to run with
+        if let Some(num_ctx) = details.parameters.as_deref().and_then(modelfile_num_ctx) {
+            return Some(num_ctx);
+        }
+        // Otherwise only the trained maximum is known, and allocating all of it
+        // (often 128k) would need far more memory than a local model has
+        details
+            .context_length
+            .map(|n| n.min(DEFAULT_CONTEXT_LENGTH as u64) as u32)
+    }
+
+    async fn list_models(&self, client: &Client) -> Result<Vec<ModelInfo>, String> {
+        let url = format!("{}/api/tags", self.base_url);
+        let response = with_headers(client.get(&url), &self.endpoint)
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Ollama API Error: {}", response.status()));
+        }
+
+        let tags = response
+            .json::<OllamaTagsResponse>()
+            .await
+            .map_err(|e| format!("Invalid Ollama response: {}", e))?;
+
+        Ok(tags
+            .models
+            .into_iter()
+            .map(|m| {
+                let details = m.details.unwrap_or_default();
+                ModelInfo {
+                    name: m.name,
+                    size: m.size,
+                    family: details.family,
+                    parameter_size: details.parameter_size,
+                    quantization: details.quantization_level,
+                    modified_at: m.modified_at,
+                }
+            })
+            .collect())
+    }
+
+    async fn health_check(&self, client: &Client) -> Result<(), String> {
+        let response = with_headers(client.get(&self.base_url), &self.endpoint)
+            .send()
+            .await
+            .map_err(|e| connect_error("Ollama", &e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Ollama API Error: {}", response.status()));
+        }
+        Ok(())
+    }
+
+    async fn backend_info(&self, client: &Client) -> Result<BackendInfo, String> {
+        let (version, models, running) = tokio::join!(
+            self.version(client),
+            self.list_models(client),
+            self.running_models(client)
+        );
+        // Older servers have no /api/ps; the rest of the report still stands
+        let loaded_models = match running {
+            Ok(running) => running.into_iter().map(loaded_model).collect(),
+            Err(e) => {
+                println!("⚠️ Couldn't list loaded Ollama models: {}", e);
+                Vec::new()
+            }
+        };
+
+        Ok(BackendInfo {
+            version: Some(version?),
+            models_available: Some(models?.len()),
+            loaded_models,
+        })
+    }
+}
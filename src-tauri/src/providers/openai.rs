@@ -0,0 +1,273 @@
+use super::{
+    api_error, connect_error, read_sse_data, stream_ended_early, with_headers, ChatRequest,
+    GenerationSummary, LlmProvider, ProviderError,
+};
+use crate::config::ProviderEndpoint;
+use crate::keychain;
+use crate::models::ollama::{
+    ChatMessage, FunctionTool, LMStudioContent, LMStudioContentPart, LMStudioFunctionCall,
+    LMStudioImageUrl, LMStudioMessage, LMStudioRequest, LMStudioResponse, LMStudioStreamOptions,
+    LMStudioToolCall, ModelInfo, OpenAIModelsResponse, ToolCall,
+};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+
+// OpenAI's /v1/models doesn't report context windows; more specific prefixes first
+const KNOWN_CONTEXT_LENGTHS: &[(&str, u32)] = &[
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4", 8_192),
+    ("gpt-3.5-turbo", 16_385),
+    ("o1-mini", 128_000),
+    ("o1-preview", 128_000),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("o4-mini", 200_000),
+];
+
+// Also matches OpenRouter-style IDs such as "openai/gpt-4o"
+fn known_context_length(model: &str) -> Option<u32> {
+    let name = model.rsplit('/').next().unwrap_or(model);
+    KNOWN_CONTEXT_LENGTHS
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, tokens)| *tokens)
+}
+
+// Any server speaking the OpenAI `/v1/chat/completions` dialect (LM Studio, OpenAI, OpenRouter...)
+pub struct OpenAICompatibleProvider {
+    name: String,
+    base_url: String,
+    endpoint: ProviderEndpoint,
+    // Keychain entry holding the bearer token, for hosted APIs
+    api_key_id: Option<String>,
+}
+
+impl OpenAICompatibleProvider {
+    pub fn new(name: &str, endpoint: ProviderEndpoint) -> Self {
+        Self {
+            name: name.to_string(),
+            base_url: endpoint.base_url.trim_end_matches('/').to_string(),
+            endpoint,
+            api_key_id: None,
+        }
+    }
+
+    pub fn with_api_key(mut self, keychain_id: &str) -> Self {
+        self.api_key_id = Some(keychain_id.to_string());
+        self
+    }
+
+    fn authorize(&self, mut builder: RequestBuilder) -> Result<RequestBuilder, String> {
+        if let Some(key_id) = &self.api_key_id {
+            builder = builder.bearer_auth(keychain::require_api_key(key_id, &self.name)?);
+        }
+        Ok(with_headers(builder, &self.endpoint))
+    }
+
+    async fn fetch_models(&self, client: &Client) -> Result<OpenAIModelsResponse, String> {
+        let url = format!("{}/models", self.base_url);
+        let response = self
+            .authorize(client.get(&url))?
+            .send()
+            .await
+            .map_err(|e| connect_error(&self.name, &e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("{} API Error: {}", self.name, response.status()));
+        }
+
+        response
+            .json::<OpenAIModelsResponse>()
+            .await
+            .map_err(|e| format!("Invalid {} response: {}", self.name, e))
+    }
+}
+
+// Images go out as data URLs in `image_url` parts; text-only messages stay plain strings
+fn openai_content(message: &ChatMessage) -> LMStudioContent {
+    let images = message.images();
+    if images.is_empty() {
+        return LMStudioContent::Text(message.text());
+    }
+
+    let mut parts = vec![LMStudioContentPart::Text {
+        text: message.text(),
+    }];
+    parts.extend(
+        images
+            .into_iter()
+            .map(|(mime_type, data)| LMStudioContentPart::ImageUrl {
+                image_url: LMStudioImageUrl {
+                    url: format!("data:{};base64,{}", mime_type, data),
+                },
+            }),
+    );
+    LMStudioContent::Parts(parts)
+}
+
+fn openai_tool_call(call: &ToolCall) -> LMStudioToolCall {
+    LMStudioToolCall {
+        id: call.id.clone(),
+        call_type: "function".to_string(),
+        function: LMStudioFunctionCall {
+            name: call.name.clone(),
+            arguments: call.arguments.to_string(),
+        },
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAICompatibleProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn stream_chat(
+        &self,
+        client: &Client,
+        request: &ChatRequest,
+        on_token: &mut (dyn FnMut(String) + Send),
+    ) -> Result<GenerationSummary, ProviderError> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let body = LMStudioRequest {
+            model: request.model.clone(),
+            messages: request
+                .messages
+                .iter()
+                .map(|m| LMStudioMessage {
+                    role: m.role.as_str().to_string(),
+                    content: openai_content(m),
+                    tool_calls: (!m.tool_calls.is_empty())
+                        .then(|| m.tool_calls.iter().map(openai_tool_call).collect()),
+                    tool_call_id: m.tool_call_id.clone(),
+                })
+                .collect(),
+            stream: true,
+            temperature: request.options.temperature(),
+            top_p: request.options.top_p,
+            max_tokens: request.options.max_tokens,
+            stop: request.options.stop.clone(),
+            stream_options: Some(LMStudioStreamOptions {
+                include_usage: true,
+            }),
+            tools: (!request.tools.is_empty())
+                .then(|| request.tools.iter().map(FunctionTool::from).collect()),
+        };
+
+        let response = self
+            .authorize(client.post(&url))?
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| connect_error(&self.name, &e))?;
+
+        if !response.status().is_success() {
+            return Err(api_error(&self.name, response.status()));
+        }
+
+        let mut summary = GenerationSummary::default();
+        // Some servers skip [DONE], but every one sends a finish reason
+        let mut finished = false;
+        // (id, name, arguments so far) per tool call index
+        let mut pending_calls: Vec<(String, String, String)> = Vec::new();
+
+        read_sse_data(response, &self.name, |data| {
+            if data == "[DONE]" {
+                finished = true;
+                return Ok(false);
+            }
+
+            let Ok(res) = serde_json::from_str::<LMStudioResponse>(data) else {
+                return Ok(true);
+            };
+
+            if let Some(error) = res.error {
+                return Err(format!("{} error: {}", self.name, error.message));
+            }
+
+            if let Some(choice) = res.choices.first() {
+                if let Some(content) = choice.delta.as_ref().and_then(|d| d.content.clone()) {
+                    if !content.is_empty() {
+                        on_token(content);
+                    }
+                }
+                for delta in choice
+                    .delta
+                    .as_ref()
+                    .and_then(|d| d.tool_calls.as_ref())
+                    .into_iter()
+                    .flatten()
+                {
+                    if pending_calls.len() <= delta.index {
+                        pending_calls.resize(delta.index + 1, Default::default());
+                    }
+                    let (id, name, arguments) = &mut pending_calls[delta.index];
+                    if let Some(new_id) = &delta.id {
+                        *id = new_id.clone();
+                    }
+                    if let Some(function) = &delta.function {
+                        name.push_str(function.name.as_deref().unwrap_or(""));
+                        arguments.push_str(function.arguments.as_deref().unwrap_or(""));
+                    }
+                }
+                if choice.finish_reason.is_some() {
+                    finished = true;
+                    summary.finish_reason = choice.finish_reason.clone();
+                }
+            }
+
+            if let Some(usage) = res.usage {
+                summary.prompt_tokens = usage.prompt_tokens;
+                summary.completion_tokens = usage.completion_tokens;
+            }
+
+            Ok(true)
+        })
+        .await?;
+
+        if !finished {
+            return Err(stream_ended_early(&self.name));
+        }
+        summary.tool_calls = pending_calls
+            .into_iter()
+            .map(|(id, name, arguments)| ToolCall {
+                id,
+                name,
+                // Models occasionally emit broken JSON; let the tool report it
+                arguments: serde_json::from_str(&arguments)
+                    .unwrap_or(serde_json::Value::String(arguments)),
+            })
+            .collect();
+
+        Ok(summary)
+    }
+
+    async fn context_length(&self, client: &Client, model: &str) -> Option<u32> {
+        // OpenRouter reports the window; OpenAI and LM Studio don't
+        let reported = match self.fetch_models(client).await {
+            Ok(models) => models
+                .data
+                .into_iter()
+                .find(|m| m.id == model)
+                .and_then(|m| m.context_length),
+            Err(_) => None,
+        };
+        reported.or_else(|| known_context_length(model))
+    }
+
+    async fn list_models(&self, client: &Client) -> Result<Vec<ModelInfo>, String> {
+        Ok(self
+            .fetch_models(client)
+            .await?
+            .data
+            .into_iter()
+            .map(|m| ModelInfo::named(m.id))
+            .collect())
+    }
+
+    async fn health_check(&self, client: &Client) -> Result<(), String> {
+        self.list_models(client).await.map(|_| ())
+    }
+}
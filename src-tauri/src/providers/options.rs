@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_TEMPERATURE: f32 = 0.7;
+// OpenAI rejects more than four stop sequences; keep every backend to the same limit
+const MAX_STOP_SEQUENCES: usize = 4;
+
+// Sampling parameters as sent by the frontend; anything left out uses the backend default
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GenerationOptions {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub num_ctx: Option<u32>,
+    pub stop: Option<Vec<String>>,
+}
+
+impl GenerationOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(format!(
+                    "temperature must be between 0 and 2, got {}",
+                    temperature
+                ));
+            }
+        }
+
+        if let Some(top_p) = self.top_p {
+            if !(top_p > 0.0 && top_p <= 1.0) {
+                return Err(format!("top_p must be in (0, 1], got {}", top_p));
+            }
+        }
+
+        if self.max_tokens == Some(0) {
+            return Err("max_tokens must be at least 1".to_string());
+        }
+
+        if self.num_ctx == Some(0) {
+            return Err("num_ctx must be at least 1".to_string());
+        }
+
+        if let Some(stop) = &self.stop {
+            if stop.len() > MAX_STOP_SEQUENCES {
+                return Err(format!(
+                    "At most {} stop sequences are allowed, got {}",
+                    MAX_STOP_SEQUENCES,
+                    stop.len()
+                ));
+            }
+            if stop.iter().any(|s| s.is_empty()) {
+                return Err("Stop sequences must not be empty".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn temperature(&self) -> f32 {
+        self.temperature.unwrap_or(DEFAULT_TEMPERATURE)
+    }
+}
@@ -0,0 +1,365 @@
+use super::{connect_error, ProviderError};
+use futures_util::StreamExt;
+use reqwest::Response;
+
+/// Splits a byte stream into lines, no matter how the transport chunked it.
+///
+/// Bytes are buffered until a `\n` arrives, so a line (or a multi-byte UTF-8
+/// character) split across two chunks is only decoded once it is complete.
+#[derive(Default)]
+pub struct LineDecoder {
+    buffer: Vec<u8>,
+}
+
+impl LineDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a chunk and returns every line it completed, without the line ending.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut lines = Vec::new();
+        let mut start = 0;
+        while let Some(pos) = self.buffer[start..].iter().position(|&b| b == b'\n') {
+            let end = start + pos;
+            lines.push(decode_line(&self.buffer[start..end]));
+            start = end + 1;
+        }
+        self.buffer.drain(..start);
+
+        lines
+    }
+
+    /// Returns whatever is left once the stream has ended without a final newline.
+    pub fn finish(&mut self) -> Option<String> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let line = decode_line(&self.buffer);
+        self.buffer.clear();
+        Some(line)
+    }
+}
+
+fn decode_line(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+}
+
+/// Server-Sent Events framing on top of [`LineDecoder`]: `data:` lines are
+/// accumulated until a blank line dispatches the event.
+#[derive(Default)]
+pub struct SseDecoder {
+    lines: LineDecoder,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        let mut events = Vec::new();
+        for line in self.lines.push(chunk) {
+            if let Some(event) = self.process_line(&line) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Flushes an event the server never terminated with a blank line.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        if let Some(line) = self.lines.finish() {
+            if let Some(event) = self.process_line(&line) {
+                return Some(event);
+            }
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        // Lines starting with ':' are comments (often used as keep-alives)
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "data" => self.data.push(value.to_string()),
+            "event" => self.event = Some(value.to_string()),
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return None;
+        }
+
+        Some(SseEvent {
+            event,
+            data: std::mem::take(&mut self.data).join("\n"),
+        })
+    }
+}
+
+/// Feeds every non-empty line of a newline-delimited JSON response to `on_line`.
+/// Reading stops as soon as `on_line` returns `Ok(false)`. A connection that
+/// fails mid-body is reported like one that failed to connect, as `name`'s.
+pub(crate) async fn read_ndjson_lines(
+    response: Response,
+    name: &str,
+    mut on_line: impl FnMut(&str) -> Result<bool, String> + Send,
+) -> Result<(), ProviderError> {
+    let mut decoder = LineDecoder::new();
+    let mut stream = response.bytes_stream();
+
+    while let Some(item) = stream.next().await {
+        let chunk = item.map_err(|e| connect_error(name, &e))?;
+
+        for line in decoder.push(&chunk) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if !on_line(&line)? {
+                return Ok(());
+            }
+        }
+    }
+
+    if let Some(line) = decoder.finish() {
+        if !line.trim().is_empty() {
+            on_line(&line)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Feeds the data of every Server-Sent Event in the response to `on_data`.
+/// Reading stops as soon as `on_data` returns `Ok(false)`.
+pub(crate) async fn read_sse_data(
+    response: Response,
+    name: &str,
+    mut on_data: impl FnMut(&str) -> Result<bool, String> + Send,
+) -> Result<(), ProviderError> {
+    let mut decoder = SseDecoder::new();
+    let mut stream = response.bytes_stream();
+
+    while let Some(item) = stream.next().await {
+        let chunk = item.map_err(|e| connect_error(name, &e))?;
+
+        for event in decoder.push(&chunk) {
+            if !on_data(&event.data)? {
+                return Ok(());
+            }
+        }
+    }
+
+    if let Some(event) = decoder.finish() {
+        on_data(&event.data)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Small seeded xorshift, so a failing split can be reproduced
+    struct Splitter(u64);
+
+    impl Splitter {
+        fn next(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as usize
+        }
+
+        fn split<'a>(&mut self, input: &'a [u8]) -> Vec<&'a [u8]> {
+            let mut chunks = Vec::new();
+            let mut rest = input;
+            while !rest.is_empty() {
+                let len = 1 + self.next(rest.len().min(8));
+                let (chunk, tail) = rest.split_at(len);
+                chunks.push(chunk);
+                rest = tail;
+            }
+            chunks
+        }
+    }
+
+    // One byte at a time, then a batch of random splits
+    fn chunkings(input: &[u8]) -> Vec<Vec<&[u8]>> {
+        let mut all = vec![input.chunks(1).collect(), vec![input]];
+        for seed in 1..=50u64 {
+            all.push(Splitter(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15)).split(input));
+        }
+        all
+    }
+
+    fn decode_lines(chunks: &[&[u8]]) -> Vec<String> {
+        let mut decoder = LineDecoder::new();
+        let mut lines: Vec<String> = chunks.iter().flat_map(|c| decoder.push(c)).collect();
+        lines.extend(decoder.finish());
+        lines
+    }
+
+    fn decode_events(chunks: &[&[u8]]) -> Vec<SseEvent> {
+        let mut decoder = SseDecoder::new();
+        let mut events: Vec<SseEvent> = chunks.iter().flat_map(|c| decoder.push(c)).collect();
+        events.extend(decoder.finish());
+        events
+    }
+
+    fn data(data: &str) -> SseEvent {
+        SseEvent {
+            event: None,
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn lines_keep_multibyte_characters_split_across_chunks() {
+        let input = "{\"content\":\"héllo 🦀\"}\n{\"content\":\"日本語\"}\n".as_bytes();
+        for chunks in chunkings(input) {
+            assert_eq!(
+                decode_lines(&chunks),
+                ["{\"content\":\"héllo 🦀\"}", "{\"content\":\"日本語\"}"]
+            );
+        }
+    }
+
+    #[test]
+    fn lines_strip_crlf_endings() {
+        let input = b"first\r\nsecond\r\n\r\nthird\n";
+        for chunks in chunkings(input) {
+            assert_eq!(decode_lines(&chunks), ["first", "second", "", "third"]);
+        }
+    }
+
+    #[test]
+    fn lines_finish_returns_an_unterminated_last_line() {
+        let input = b"{\"done\":false}\n{\"done\":true}";
+        for chunks in chunkings(input) {
+            assert_eq!(
+                decode_lines(&chunks),
+                ["{\"done\":false}", "{\"done\":true}"]
+            );
+        }
+
+        let mut decoder = LineDecoder::new();
+        assert_eq!(decoder.push(b"complete\n"), ["complete"]);
+        assert_eq!(decoder.finish(), None);
+    }
+
+    #[test]
+    fn sse_joins_multi_line_data_events() {
+        let input = b"data: first line\ndata: second line\n\ndata: {\"x\":1}\n\n";
+        for chunks in chunkings(input) {
+            assert_eq!(
+                decode_events(&chunks),
+                [data("first line\nsecond line"), data("{\"x\":1}")]
+            );
+        }
+    }
+
+    #[test]
+    fn sse_skips_keep_alive_comments() {
+        let input = b": ping\n\ndata: a\n: still here\n\n:\n\ndata: [DONE]\n\n";
+        for chunks in chunkings(input) {
+            assert_eq!(decode_events(&chunks), [data("a"), data("[DONE]")]);
+        }
+    }
+
+    #[test]
+    fn sse_handles_crlf_and_named_events() {
+        let input =
+            "event: message_delta\r\ndata: {\"text\":\"ü\"}\r\n\r\ndata: b\r\n\r\n".as_bytes();
+        for chunks in chunkings(input) {
+            let events = decode_events(&chunks);
+            assert_eq!(
+                events,
+                [
+                    SseEvent {
+                        event: Some("message_delta".to_string()),
+                        data: "{\"text\":\"ü\"}".to_string(),
+                    },
+                    data("b"),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn sse_finish_flushes_an_unterminated_event() {
+        let input = "data: {\"content\":\"end 🦀\"}".as_bytes();
+        for chunks in chunkings(input) {
+            assert_eq!(decode_events(&chunks), [data("{\"content\":\"end 🦀\"}")]);
+        }
+
+        // Ended after the data line but before the blank line
+        let input = b"data: a\n\ndata: b\n";
+        for chunks in chunkings(input) {
+            assert_eq!(decode_events(&chunks), [data("a"), data("b")]);
+        }
+    }
+
+    #[tokio::test]
+    async fn a_body_cut_short_counts_as_a_connection_failure() {
+        use crate::providers::ErrorKind;
+        use tokio::io::AsyncWriteExt;
+
+        // Promises more body than it sends, then hangs up
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let head = "HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n";
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(b"{\"n\":1}\n").await.unwrap();
+        });
+
+        let response = reqwest::get(&url).await.unwrap();
+        let mut lines = Vec::new();
+        let error = read_ndjson_lines(response, "Ollama", |line| {
+            lines.push(line.to_string());
+            Ok(true)
+        })
+        .await
+        .unwrap_err();
+
+        assert_eq!(lines, [r#"{"n":1}"#]);
+        assert_eq!(error.kind, ErrorKind::Connect);
+        assert!(error.is_retryable());
+        assert!(
+            error.message.starts_with("Failed to connect to Ollama"),
+            "{}",
+            error
+        );
+    }
+}
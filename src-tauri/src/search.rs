@@ -1,7 +1,8 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use walkdir::WalkDir;
+use std::path::Path;
+use walkdir::{DirEntry, WalkDir};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SearchResult {
@@ -12,6 +13,38 @@ pub struct SearchResult {
     pub match_end: usize,
 }
 
+// Skip hidden files and common ignore patterns
+pub(crate) fn is_project_entry(entry: &DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    !name.starts_with('.')
+        && name != "node_modules"
+        && name != "target"
+        && name != "dist"
+        && name != "build"
+}
+
+pub(crate) fn is_binary_path(path: &Path) -> bool {
+    let Some(ext) = path.extension() else {
+        return false;
+    };
+    matches!(
+        ext.to_string_lossy().to_lowercase().as_str(),
+        "exe"
+            | "dll"
+            | "so"
+            | "dylib"
+            | "png"
+            | "jpg"
+            | "jpeg"
+            | "gif"
+            | "ico"
+            | "woff"
+            | "woff2"
+            | "ttf"
+            | "eot"
+    )
+}
+
 #[tauri::command]
 pub async fn search_in_files(
     project_path: String,
@@ -45,15 +78,7 @@ pub async fn search_in_files(
         .follow_links(false)
         .max_depth(20)
         .into_iter()
-        .filter_entry(|e| {
-            // Skip hidden files and common ignore patterns
-            let name = e.file_name().to_string_lossy();
-            !name.starts_with('.')
-                && name != "node_modules"
-                && name != "target"
-                && name != "dist"
-                && name != "build"
-        })
+        .filter_entry(is_project_entry)
     {
         if results.len() >= limit {
             break;
@@ -68,26 +93,8 @@ pub async fn search_in_files(
 
         // Skip binary files (basic check)
         let path = entry.path();
-        if let Some(ext) = path.extension() {
-            let ext_str = ext.to_string_lossy().to_lowercase();
-            if matches!(
-                ext_str.as_str(),
-                "exe"
-                    | "dll"
-                    | "so"
-                    | "dylib"
-                    | "png"
-                    | "jpg"
-                    | "jpeg"
-                    | "gif"
-                    | "ico"
-                    | "woff"
-                    | "woff2"
-                    | "ttf"
-                    | "eot"
-            ) {
-                continue;
-            }
+        if is_binary_path(path) {
+            continue;
         }
 
         // Read file content
@@ -0,0 +1,809 @@
+use crate::commands::{get_projects_dir, ProjectData};
+use crate::models::ollama::{ChatMessage, Role};
+use crate::providers::GenerationSummary;
+use crate::search::SearchResult;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+const DEFAULT_SESSION_TITLE: &str = "New chat";
+const DEFAULT_MAX_SEARCH_RESULTS: usize = 50;
+// Long lines (minified code, pasted logs) are cut down to a window around the match
+const SNIPPET_CHARS: usize = 200;
+// Bump when the search index format changes so old indexes get rebuilt
+const SEARCH_INDEX_VERSION: u32 = 1;
+// Next to a project's sessions: the project as the frontend names it, which
+// search hands back so a hit can be resumed
+const PROJECT_MARKER: &str = "project.txt";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StoredMessage {
+    pub timestamp: u64, // unix millis
+    pub message: ChatMessage,
+    pub model: Option<String>,
+    pub provider: Option<String>,
+    pub usage: Option<GenerationSummary>,
+}
+
+// Sessions are append-only JSONL files. A rename appends a new title record
+// instead of rewriting the file; the last one wins.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SessionRecord {
+    Title {
+        title: String,
+        timestamp: u64,
+        forked_from: Option<String>,
+    },
+    Message(StoredMessage),
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SessionInfo {
+    pub id: String,
+    pub title: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub message_count: usize,
+    pub forked_from: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Session {
+    pub info: SessionInfo,
+    pub messages: Vec<StoredMessage>,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Stable across Rust releases, unlike DefaultHasher, so directory names survive upgrades
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// Named after the project's folder for browsing, and keyed by a hash of its
+// canonical path so two projects with the same folder name stay apart
+fn project_data_dir(project: &str) -> Result<PathBuf, String> {
+    let key = if Path::new(project).is_absolute() {
+        fs::canonicalize(project)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| project.to_string())
+    } else {
+        project.to_string()
+    };
+    let folder = Path::new(&key)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name: String = folder
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('.');
+    if name.is_empty() {
+        return Err(format!("Invalid project name: {}", project));
+    }
+    Ok(get_projects_dir().join(format!("{}-{:016x}", name, fnv1a(&key))))
+}
+
+fn sessions_dir(project: &str) -> Result<PathBuf, String> {
+    Ok(project_data_dir(project)?.join("sessions"))
+}
+
+fn session_path(project: &str, session_id: &str) -> Result<PathBuf, String> {
+    let valid = !session_id.is_empty()
+        && session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid {
+        return Err(format!("Invalid session ID: {}", session_id));
+    }
+    Ok(sessions_dir(project)?.join(format!("{}.jsonl", session_id)))
+}
+
+fn append_records(path: &Path, records: &[SessionRecord]) -> Result<(), String> {
+    let mut lines = String::new();
+    for record in records {
+        lines.push_str(&serde_json::to_string(record).map_err(|e| e.to_string())?);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open session {}: {}", path.display(), e))?;
+    file.write_all(lines.as_bytes())
+        .map_err(|e| format!("Failed to write session {}: {}", path.display(), e))
+}
+
+fn read_session(project: &str, session_id: &str) -> Result<Session, String> {
+    let path = session_path(project, session_id)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read session {}: {}", session_id, e))?;
+
+    let mut info = SessionInfo {
+        id: session_id.to_string(),
+        title: DEFAULT_SESSION_TITLE.to_string(),
+        created_at: 0,
+        updated_at: 0,
+        message_count: 0,
+        forked_from: None,
+    };
+    let mut messages = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        // A crash mid-append can leave a torn last line; keep everything before it
+        let record: SessionRecord = match serde_json::from_str(line) {
+            Ok(record) => record,
+            Err(e) => {
+                println!(
+                    "⚠️ Skipping bad line {} in session {}: {}",
+                    index + 1,
+                    session_id,
+                    e
+                );
+                continue;
+            }
+        };
+
+        let timestamp = match record {
+            SessionRecord::Title {
+                title,
+                timestamp,
+                forked_from,
+            } => {
+                info.title = title;
+                if forked_from.is_some() {
+                    info.forked_from = forked_from;
+                }
+                timestamp
+            }
+            SessionRecord::Message(message) => {
+                let timestamp = message.timestamp;
+                messages.push(message);
+                timestamp
+            }
+        };
+        if info.created_at == 0 {
+            info.created_at = timestamp;
+        }
+        info.updated_at = info.updated_at.max(timestamp);
+    }
+
+    info.message_count = messages.len();
+    Ok(Session { info, messages })
+}
+
+fn new_session(
+    project: &str,
+    title: Option<String>,
+    forked_from: Option<String>,
+    messages: Vec<StoredMessage>,
+) -> Result<SessionInfo, String> {
+    let dir = sessions_dir(project)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    if let Some(marker) = dir.parent().map(|parent| parent.join(PROJECT_MARKER)) {
+        if !marker.exists() {
+            fs::write(&marker, project).map_err(|e| e.to_string())?;
+        }
+    }
+
+    // Millisecond IDs sort by creation time; bump on the rare collision
+    let mut stamp = now_millis();
+    let (id, path) = loop {
+        let id = format!("session-{}", stamp);
+        let path = dir.join(format!("{}.jsonl", id));
+        if !path.exists() {
+            break (id, path);
+        }
+        stamp += 1;
+    };
+
+    let title = title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| DEFAULT_SESSION_TITLE.to_string());
+
+    let mut records = vec![SessionRecord::Title {
+        title,
+        timestamp: now_millis(),
+        forked_from,
+    }];
+    records.extend(messages.into_iter().map(SessionRecord::Message));
+    append_records(&path, &records)?;
+
+    println!("💬 Created session {} for {}", id, project);
+    Ok(read_session(project, &id)?.info)
+}
+
+#[tauri::command]
+pub async fn create_session(project: String, title: Option<String>) -> Result<SessionInfo, String> {
+    new_session(&project, title, None, Vec::new())
+}
+
+/// Sessions for a project, most recently updated first.
+#[tauri::command]
+pub async fn list_sessions(project: String) -> Result<Vec<SessionInfo>, String> {
+    let dir = sessions_dir(&project)?;
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut sessions = Vec::new();
+    let entries = fs::read_dir(dir).map_err(|e| e.to_string())?;
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(id) = file_name
+            .to_str()
+            .and_then(|name| name.strip_suffix(".jsonl"))
+        else {
+            continue;
+        };
+        match read_session(&project, id) {
+            Ok(session) => sessions.push(session.info),
+            Err(e) => println!("⚠️ {}", e),
+        }
+    }
+
+    sessions.sort_by_key(|s| Reverse(s.updated_at));
+    Ok(sessions)
+}
+
+#[tauri::command]
+pub async fn load_session(project: String, session_id: String) -> Result<Session, String> {
+    read_session(&project, &session_id)
+}
+
+#[tauri::command]
+pub async fn append_session_message(
+    project: String,
+    session_id: String,
+    message: ChatMessage,
+    model: Option<String>,
+    provider: Option<String>,
+    usage: Option<GenerationSummary>,
+) -> Result<StoredMessage, String> {
+    message.validate()?;
+    let path = session_path(&project, &session_id)?;
+    if !path.exists() {
+        return Err(format!("Session not found: {}", session_id));
+    }
+
+    let stored = StoredMessage {
+        timestamp: now_millis(),
+        message,
+        model,
+        provider,
+        usage,
+    };
+    append_records(&path, &[SessionRecord::Message(stored.clone())])?;
+    Ok(stored)
+}
+
+#[tauri::command]
+pub async fn rename_session(
+    project: String,
+    session_id: String,
+    title: String,
+) -> Result<SessionInfo, String> {
+    let title = title.trim().to_string();
+    if title.is_empty() {
+        return Err("Session title cannot be empty".to_string());
+    }
+    let path = session_path(&project, &session_id)?;
+    if !path.exists() {
+        return Err(format!("Session not found: {}", session_id));
+    }
+
+    append_records(
+        &path,
+        &[SessionRecord::Title {
+            title,
+            timestamp: now_millis(),
+            forked_from: None,
+        }],
+    )?;
+    Ok(read_session(&project, &session_id)?.info)
+}
+
+/// Copies a session into a new one, keeping the first `message_count` messages
+/// (all of them if not given), so a conversation can branch from any point.
+#[tauri::command]
+pub async fn fork_session(
+    project: String,
+    session_id: String,
+    message_count: Option<usize>,
+    title: Option<String>,
+) -> Result<SessionInfo, String> {
+    let source = read_session(&project, &session_id)?;
+    let mut messages = source.messages;
+    if let Some(count) = message_count {
+        if count > messages.len() {
+            return Err(format!(
+                "Session {} only has {} messages",
+                session_id,
+                messages.len()
+            ));
+        }
+        messages.truncate(count);
+    }
+
+    let title = title.or_else(|| Some(format!("{} (fork)", source.info.title)));
+    new_session(&project, title, Some(session_id), messages)
+}
+
+#[tauri::command]
+pub async fn delete_session(project: String, session_id: String) -> Result<(), String> {
+    let path = session_path(&project, &session_id)?;
+    fs::remove_file(&path)
+        .map_err(|e| format!("Failed to delete session {}: {}", session_id, e))?;
+    println!("🗑️ Deleted session {} from {}", session_id, project);
+    Ok(())
+}
+
+// One message matching a conversation search. `location` reuses the file search
+// shape: file_path is the session file, line_number/line_content the best line.
+#[derive(Debug, Serialize)]
+pub struct ConversationHit {
+    pub project: String,
+    pub session_id: Option<String>, // None for chat history saved with save_project
+    pub session_title: String,
+    pub message_index: usize,
+    pub role: Option<Role>,
+    pub timestamp: Option<u64>,
+    pub in_code_block: bool,
+    pub score: u32,
+    #[serde(flatten)]
+    pub location: SearchResult,
+}
+
+struct QueryPatterns {
+    terms: Vec<Regex>,
+    phrase: Option<Regex>,
+}
+
+impl QueryPatterns {
+    fn new(query: &str) -> Result<Self, String> {
+        let mut words: Vec<&str> = query.split_whitespace().collect();
+        if words.is_empty() {
+            return Err("Search query cannot be empty".to_string());
+        }
+        let phrase = (words.len() > 1).then(|| words.join(" "));
+        words.sort_unstable();
+        words.dedup();
+
+        let pattern = |text: &str| {
+            Regex::new(&format!("(?i){}", regex::escape(text)))
+                .map_err(|e| format!("Invalid search query: {}", e))
+        };
+        Ok(QueryPatterns {
+            terms: words.into_iter().map(pattern).collect::<Result<_, _>>()?,
+            phrase: phrase.as_deref().map(pattern).transpose()?,
+        })
+    }
+}
+
+fn snippet(line: &str, start: usize, end: usize) -> (String, usize, usize) {
+    if line.len() <= SNIPPET_CHARS {
+        return (line.to_string(), start, end);
+    }
+    let mut from = start.saturating_sub(SNIPPET_CHARS / 4);
+    while !line.is_char_boundary(from) {
+        from -= 1;
+    }
+    let mut to = (from + SNIPPET_CHARS).max(end).min(line.len());
+    while !line.is_char_boundary(to) {
+        to += 1;
+    }
+    (line[from..to].to_string(), start - from, end - from)
+}
+
+// Scores a message (all terms must appear somewhere in it) and picks the line
+// to show. Phrase matches and lines inside code blocks rank higher, since
+// that's usually where the answer someone is looking for lives.
+fn score_message(text: &str, patterns: &QueryPatterns) -> Option<(u32, usize, bool, usize, usize)> {
+    let mut score = 0;
+    for term in &patterns.terms {
+        let count = term.find_iter(text).count();
+        if count == 0 {
+            return None;
+        }
+        score += count.min(5) as u32;
+    }
+    if patterns.phrase.as_ref().is_some_and(|p| p.is_match(text)) {
+        score += 10;
+    }
+
+    let mut best: Option<(u32, usize, bool, usize, usize)> = None;
+    let mut in_code = false;
+    for (index, line) in text.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+
+        let phrase_match = patterns.phrase.as_ref().and_then(|p| p.find(line));
+        let term_matches: Vec<_> = patterns.terms.iter().filter_map(|t| t.find(line)).collect();
+        let Some(first) =
+            phrase_match.or_else(|| term_matches.iter().min_by_key(|m| m.start()).copied())
+        else {
+            continue;
+        };
+
+        let line_score = term_matches.len() as u32 * 2
+            + if phrase_match.is_some() { 5 } else { 0 }
+            + if in_code { 2 } else { 0 };
+        if best.is_none_or(|(s, ..)| line_score > s) {
+            best = Some((line_score, index, in_code, first.start(), first.end()));
+        }
+    }
+
+    best.map(|(line_score, line, in_code, start, end)| {
+        (score + line_score, line, in_code, start, end)
+    })
+}
+
+fn search_text(text: &str, patterns: &QueryPatterns) -> Option<(u32, bool, SearchResult)> {
+    let (score, line_index, in_code, start, end) = score_message(text, patterns)?;
+    let line = text.lines().nth(line_index)?;
+    let (line_content, match_start, match_end) = snippet(line, start, end);
+    Some((
+        score,
+        in_code,
+        SearchResult {
+            file_path: String::new(),
+            line_number: line_index + 1,
+            line_content,
+            match_start,
+            match_end,
+        },
+    ))
+}
+
+// Session files only ever grow, so the index remembers how far it has read each
+// one and a refresh parses just what was appended since. Rebuildable, so it
+// lives in the cache dir rather than next to the projects.
+fn conversation_index_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or(PathBuf::from("."))
+        .join("vibe-studio")
+        .join("conversation-index.json")
+}
+
+/// Message text of every saved conversation, kept in memory between searches
+/// and persisted so a restart doesn't have to re-read every session.
+#[derive(Default)]
+pub struct ConversationIndex {
+    loaded: Mutex<Option<IndexedSources>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct IndexedSources {
+    version: u32,
+    sources: HashMap<String, IndexedSource>, // keyed by file path
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexedSource {
+    project: String,
+    session_id: Option<String>, // None for chat history saved with save_project
+    title: String,
+    modified: u64,
+    indexed_bytes: u64,
+    messages: Vec<IndexedMessage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexedMessage {
+    role: Option<Role>,
+    timestamp: Option<u64>,
+    text: String,
+}
+
+fn load_conversation_index() -> IndexedSources {
+    fs::read_to_string(conversation_index_path())
+        .ok()
+        .and_then(|json| serde_json::from_str::<IndexedSources>(&json).ok())
+        .filter(|index| index.version == SEARCH_INDEX_VERSION)
+        .unwrap_or(IndexedSources {
+            version: SEARCH_INDEX_VERSION,
+            sources: HashMap::new(),
+        })
+}
+
+fn save_conversation_index(index: &IndexedSources) -> Result<(), String> {
+    let path = conversation_index_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string(index).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to save conversation index: {}", e))
+}
+
+// Session files and save_project files, for one project or all of them
+fn conversation_sources(project: Option<&str>) -> Result<Vec<PathBuf>, String> {
+    let projects_dir = get_projects_dir();
+    let mut sources = Vec::new();
+    let mut add_sessions = |dir: PathBuf| {
+        if let Ok(entries) = fs::read_dir(dir) {
+            sources.extend(
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl")),
+            );
+        }
+    };
+
+    let mut saved = Vec::new();
+    match project {
+        Some(project) => {
+            add_sessions(sessions_dir(project)?);
+            saved.push(projects_dir.join(format!("{}.json", project)));
+        }
+        None => {
+            if let Ok(entries) = fs::read_dir(&projects_dir) {
+                for path in entries.flatten().map(|entry| entry.path()) {
+                    if path.is_dir() {
+                        add_sessions(path.join("sessions"));
+                    } else {
+                        saved.push(path);
+                    }
+                }
+            }
+        }
+    }
+
+    sources.extend(
+        saved
+            .into_iter()
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json")),
+    );
+    Ok(sources)
+}
+
+// Indexes the complete lines of `bytes` and returns how many bytes they took up.
+// A torn last line is left for the next refresh, once the rest of it is written.
+fn index_session_records(source: &mut IndexedSource, bytes: &[u8]) -> u64 {
+    let Some(end) = bytes.iter().rposition(|&b| b == b'\n').map(|i| i + 1) else {
+        return 0;
+    };
+    for line in String::from_utf8_lossy(&bytes[..end]).lines() {
+        // Bad lines are skipped, as when loading the session
+        match serde_json::from_str::<SessionRecord>(line) {
+            Ok(SessionRecord::Title { title, .. }) => source.title = title,
+            Ok(SessionRecord::Message(stored)) => source.messages.push(IndexedMessage {
+                role: Some(stored.message.role),
+                timestamp: Some(stored.timestamp),
+                text: stored.message.text(),
+            }),
+            Err(_) => {}
+        }
+    }
+    end as u64
+}
+
+// Chats saved with save_project are one opaque string; index it as a single message
+fn index_saved_project(path: &Path) -> IndexedSource {
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let data = fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<ProjectData>(&json).ok());
+
+    IndexedSource {
+        project: data.as_ref().map_or(name.clone(), |d| d.name.clone()),
+        session_id: None,
+        title: data.as_ref().map_or(name, |d| d.name.clone()),
+        modified: 0,
+        indexed_bytes: 0,
+        messages: data
+            .map(|d| IndexedMessage {
+                role: None,
+                timestamp: None,
+                text: d.chat_history,
+            })
+            .into_iter()
+            .collect(),
+    }
+}
+
+// The project a session file belongs to; sessions from before the project
+// marker only have their directory name to go on
+fn session_project(path: &Path) -> String {
+    let Some(project_dir) = path.parent().and_then(Path::parent) else {
+        return String::new();
+    };
+    fs::read_to_string(project_dir.join(PROJECT_MARKER))
+        .ok()
+        .or_else(|| {
+            project_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_default()
+}
+
+// Brings one file's entry up to date; returns whether anything changed
+fn refresh_source(index: &mut IndexedSources, path: &Path) -> Result<bool, String> {
+    let key = path.to_string_lossy().to_string();
+    let meta = fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", key, e))?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_millis() as u64);
+    let size = meta.len();
+    let is_session = path.extension().is_some_and(|ext| ext == "jsonl");
+    let read_from = |offset: u64| -> Result<Vec<u8>, String> {
+        let mut file = File::open(path).map_err(|e| format!("Failed to read {}: {}", key, e))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| e.to_string())?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read {}: {}", key, e))?;
+        Ok(bytes)
+    };
+
+    if let Some(source) = index.sources.get_mut(&key) {
+        if source.modified == modified && source.indexed_bytes == size {
+            return Ok(false);
+        }
+        if is_session && size > source.indexed_bytes {
+            let appended = read_from(source.indexed_bytes)?;
+            let consumed = index_session_records(source, &appended);
+            source.indexed_bytes += consumed;
+            source.modified = modified;
+            return Ok(consumed > 0);
+        }
+    }
+
+    let mut source = if is_session {
+        let mut source = IndexedSource {
+            project: session_project(path),
+            session_id: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string()),
+            title: DEFAULT_SESSION_TITLE.to_string(),
+            modified,
+            indexed_bytes: 0,
+            messages: Vec::new(),
+        };
+        source.indexed_bytes = index_session_records(&mut source, &read_from(0)?);
+        source
+    } else {
+        index_saved_project(path)
+    };
+    source.modified = modified;
+    if !is_session {
+        source.indexed_bytes = size;
+    }
+    index.sources.insert(key, source);
+    Ok(true)
+}
+
+/// Searches saved conversations (sessions and save_project chat history) for
+/// messages containing every word of `query` (case-insensitive), best matches
+/// first. Limited to one project if given.
+#[tauri::command]
+pub async fn search_conversations(
+    index: State<'_, ConversationIndex>,
+    query: String,
+    project: Option<String>,
+    max_results: Option<usize>,
+) -> Result<Vec<ConversationHit>, String> {
+    let patterns = QueryPatterns::new(&query)?;
+    let limit = max_results.unwrap_or(DEFAULT_MAX_SEARCH_RESULTS);
+    let sources = conversation_sources(project.as_deref())?;
+
+    let mut loaded = index.loaded.lock().unwrap();
+    let indexed = loaded.get_or_insert_with(load_conversation_index);
+
+    // Forget deleted sessions, then catch up on whatever was written since last time
+    let before = indexed.sources.len();
+    indexed.sources.retain(|path, _| Path::new(path).exists());
+    let mut changed = indexed.sources.len() != before;
+    for path in &sources {
+        match refresh_source(indexed, path) {
+            Ok(refreshed) => changed |= refreshed,
+            Err(e) => println!("⚠️ {}", e),
+        }
+    }
+    if changed {
+        if let Err(e) = save_conversation_index(indexed) {
+            println!("⚠️ {}", e);
+        }
+    }
+
+    let mut hits = Vec::new();
+    for path in &sources {
+        let key = path.to_string_lossy().to_string();
+        let Some(source) = indexed.sources.get(&key) else {
+            continue;
+        };
+        for (index, message) in source.messages.iter().enumerate() {
+            let Some((score, in_code_block, mut location)) = search_text(&message.text, &patterns)
+            else {
+                continue;
+            };
+            location.file_path = key.clone();
+            hits.push(ConversationHit {
+                project: source.project.clone(),
+                session_id: source.session_id.clone(),
+                session_title: source.title.clone(),
+                message_index: index,
+                role: message.role,
+                timestamp: message.timestamp,
+                in_code_block,
+                score,
+                location,
+            });
+        }
+    }
+
+    // Best score first; newer messages win ties
+    hits.sort_by_key(|hit| (Reverse(hit.score), Reverse(hit.timestamp)));
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projects_sharing_a_folder_name_get_their_own_sessions() {
+        let work = sessions_dir("/home/me/work/app").unwrap();
+        let personal = sessions_dir("/home/me/personal/app").unwrap();
+
+        assert_ne!(work, personal);
+        assert_eq!(work, sessions_dir("/home/me/work/app").unwrap());
+        let folder = work
+            .parent()
+            .unwrap()
+            .file_name()
+            .unwrap()
+            .to_string_lossy();
+        assert!(folder.starts_with("app-"), "{}", folder);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolves_symlinked_project_paths_to_one_directory() {
+        let base = std::env::temp_dir().join("sessions_dir_symlink");
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("real/app")).unwrap();
+        std::os::unix::fs::symlink(base.join("real"), base.join("link")).unwrap();
+
+        assert_eq!(
+            sessions_dir(&base.join("real/app").to_string_lossy()).unwrap(),
+            sessions_dir(&base.join("link/app").to_string_lossy()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,479 @@
+use crate::providers::mock::{load_transcript, transcript_turn, MockTranscript, MockTurn};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+// Requests may carry base64 images, but nothing legitimate gets near this
+const MAX_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+const STUB_CONTEXT_LENGTH: u32 = 8192;
+
+// The running stub, if any; starting a new one stops the old one
+#[derive(Default)]
+pub struct StubServers {
+    current: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StubServerInfo {
+    pub transcript: String,
+    pub ollama_url: String, // point the "ollama" endpoint here
+    pub openai_url: String, // or "lmstudio"/"openai" here
+}
+
+struct StubRequest {
+    method: String,
+    path: String,
+    body: Value,
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<StubRequest, String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Err("Connection closed before the request headers".to_string());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Err("Request headers too large".to_string());
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut request_line = head.lines().next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("").to_string();
+    let path = request_line.next().unwrap_or("/").to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_REQUEST_BYTES);
+
+    while buf.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let body = serde_json::from_slice(&buf[header_end..]).unwrap_or(Value::Null);
+    Ok(StubRequest { method, path, body })
+}
+
+// Bodies are close-delimited, so chunks can be written as the turn plays out
+async fn write_head(stream: &mut TcpStream, status: &str, content_type: &str) -> bool {
+    let head = format!(
+        "HTTP/1.1 {}\r\ncontent-type: {}\r\nconnection: close\r\n\r\n",
+        status, content_type
+    );
+    stream.write_all(head.as_bytes()).await.is_ok()
+}
+
+async fn write_json(stream: &mut TcpStream, status: &str, body: Value) {
+    if write_head(stream, status, "application/json").await {
+        let _ = stream.write_all(body.to_string().as_bytes()).await;
+    }
+}
+
+fn assistant_messages(body: &Value) -> usize {
+    body["messages"]
+        .as_array()
+        .map(|messages| messages.iter().filter(|m| m["role"] == "assistant").count())
+        .unwrap_or(0)
+}
+
+// Ollama /api/chat: one JSON object per line, then a `done` line with counts
+async fn stream_ollama(stream: &mut TcpStream, model: &str, turn: &MockTurn) {
+    if !write_head(stream, "200 OK", "application/x-ndjson").await {
+        return;
+    }
+
+    let tokens = turn.tokens();
+    let mut lines = Vec::new();
+    for token in &tokens {
+        lines.push(json!({
+            "model": model,
+            "message": { "role": "assistant", "content": token },
+            "done": false,
+        }));
+    }
+    if !turn.tool_calls.is_empty() {
+        let calls: Vec<Value> = turn
+            .tool_calls
+            .iter()
+            .map(|call| json!({ "function": { "name": call.name, "arguments": call.arguments } }))
+            .collect();
+        lines.push(json!({
+            "model": model,
+            "message": { "role": "assistant", "content": "", "tool_calls": calls },
+            "done": false,
+        }));
+    }
+    lines.push(match &turn.error {
+        Some(error) => json!({ "error": error }),
+        None => json!({
+            "model": model,
+            "message": { "role": "assistant", "content": "" },
+            "done": true,
+            "done_reason": turn.finish_reason(),
+            "prompt_eval_count": turn.prompt_tokens,
+            "eval_count": tokens.len(),
+            "eval_duration": turn.delay_ms * 1_000_000 * tokens.len() as u64,
+        }),
+    });
+
+    for line in lines {
+        tokio::time::sleep(turn.delay()).await;
+        if stream
+            .write_all(format!("{}\n", line).as_bytes())
+            .await
+            .is_err()
+        {
+            return; // Client went away, e.g. a cancelled generation
+        }
+    }
+}
+
+// OpenAI /v1/chat/completions: SSE chunks, a usage chunk, then [DONE]
+async fn stream_openai(stream: &mut TcpStream, model: &str, turn: &MockTurn) {
+    if !write_head(stream, "200 OK", "text/event-stream").await {
+        return;
+    }
+
+    let chunk = |delta: Value, finish_reason: Option<String>| {
+        json!({
+            "id": "chatcmpl-mock",
+            "object": "chat.completion.chunk",
+            "model": model,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }],
+        })
+    };
+
+    let tokens = turn.tokens();
+    let mut events: Vec<Value> = tokens
+        .iter()
+        .map(|token| chunk(json!({ "content": token }), None))
+        .collect();
+    for (index, call) in turn.tool_calls.iter().enumerate() {
+        events.push(chunk(
+            json!({ "tool_calls": [{
+                "index": index,
+                "id": call.id,
+                "type": "function",
+                "function": { "name": call.name, "arguments": call.arguments.to_string() },
+            }] }),
+            None,
+        ));
+    }
+    match &turn.error {
+        Some(error) => events.push(json!({ "error": { "message": error } })),
+        None => {
+            events.push(chunk(json!({}), Some(turn.finish_reason())));
+            events.push(json!({
+                "id": "chatcmpl-mock",
+                "object": "chat.completion.chunk",
+                "model": model,
+                "choices": [],
+                "usage": {
+                    "prompt_tokens": turn.prompt_tokens,
+                    "completion_tokens": tokens.len(),
+                },
+            }));
+        }
+    }
+
+    for event in events {
+        tokio::time::sleep(turn.delay()).await;
+        if stream
+            .write_all(format!("data: {}\n\n", event).as_bytes())
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+    let _ = stream.write_all(b"data: [DONE]\n\n").await;
+}
+
+async fn handle(mut stream: TcpStream, name: Arc<String>, transcript: Arc<MockTranscript>) {
+    let request = match read_request(&mut stream).await {
+        Ok(request) => request,
+        Err(e) => {
+            println!("⚠️ Stub server: {}", e);
+            return;
+        }
+    };
+
+    let route = request.path.split('?').next().unwrap_or("");
+    match (request.method.as_str(), route) {
+        ("GET", "/") => {
+            if write_head(&mut stream, "200 OK", "text/plain").await {
+                let _ = stream.write_all(b"Ollama is running").await;
+            }
+        }
+        ("GET", "/api/tags") => {
+            write_json(
+                &mut stream,
+                "200 OK",
+                json!({ "models": [{ "name": *name }] }),
+            )
+            .await
+        }
+        ("GET", "/api/version") => {
+            write_json(&mut stream, "200 OK", json!({ "version": "0.0.0-stub" })).await
+        }
+        ("GET", "/api/ps") => write_json(&mut stream, "200 OK", json!({ "models": [] })).await,
+        ("GET", "/v1/models") => {
+            write_json(&mut stream, "200 OK", json!({ "data": [{ "id": *name }] })).await
+        }
+        ("POST", "/api/show") => {
+            let info = json!({ "mock.context_length": STUB_CONTEXT_LENGTH });
+            write_json(&mut stream, "200 OK", json!({ "model_info": info })).await
+        }
+        ("POST", "/api/chat") | ("POST", "/v1/chat/completions") => {
+            let turn = match transcript_turn(&transcript, &name, assistant_messages(&request.body))
+            {
+                Ok(turn) => turn,
+                Err(e) => {
+                    write_json(&mut stream, "400 Bad Request", json!({ "error": e })).await;
+                    return;
+                }
+            };
+            let model = request.body["model"].as_str().unwrap_or(&name).to_string();
+            if route == "/api/chat" {
+                stream_ollama(&mut stream, &model, &turn).await;
+            } else {
+                stream_openai(&mut stream, &model, &turn).await;
+            }
+        }
+        _ => {
+            let error = format!("Stub server has no route for {} {}", request.method, route);
+            write_json(&mut stream, "404 Not Found", json!({ "error": error })).await
+        }
+    }
+}
+
+/// Serves `transcript` on a free local port until the returned task is aborted.
+/// `name` is the model the stub reports.
+pub(crate) async fn spawn_stub(
+    name: &str,
+    transcript: MockTranscript,
+) -> Result<(JoinHandle<()>, u16), String> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| format!("Failed to start stub server: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let name = Arc::new(name.to_string());
+    let transcript = Arc::new(transcript);
+    let task = tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handle(stream, name.clone(), transcript.clone()));
+        }
+    });
+    Ok((task, port))
+}
+
+/// Serves the named transcript on a local port, speaking both Ollama's NDJSON
+/// and OpenAI's SSE streaming formats, so the real HTTP providers can be
+/// exercised with no model installed. Replaces any stub started earlier.
+#[tauri::command]
+pub async fn start_stub_server(
+    servers: State<'_, StubServers>,
+    transcript: String,
+) -> Result<StubServerInfo, String> {
+    let (task, port) = spawn_stub(&transcript, load_transcript(&transcript)?).await?;
+
+    if let Some(previous) = servers.current.lock().unwrap().replace(task) {
+        previous.abort();
+    }
+
+    let base_url = format!("http://127.0.0.1:{}", port);
+    println!("🧪 Stub server for {} on {}", transcript, base_url);
+    Ok(StubServerInfo {
+        transcript,
+        openai_url: format!("{}/v1", base_url),
+        ollama_url: base_url,
+    })
+}
+
+#[tauri::command]
+pub async fn stop_stub_server(servers: State<'_, StubServers>) -> Result<(), String> {
+    match servers.current.lock().unwrap().take() {
+        Some(task) => {
+            task.abort();
+            Ok(())
+        }
+        None => Err("No stub server is running".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProviderEndpoint;
+    use crate::models::ollama::{ChatMessage, Role, ToolCall};
+    use crate::providers::ollama::OllamaProvider;
+    use crate::providers::openai::OpenAICompatibleProvider;
+    use crate::providers::{ChatRequest, GenerationSummary, LlmProvider, ProviderError};
+    use reqwest::Client;
+
+    const MODEL: &str = "stub-model";
+
+    fn turn(text: &str) -> MockTurn {
+        MockTurn {
+            text: Some(text.to_string()),
+            prompt_tokens: Some(12),
+            ..Default::default()
+        }
+    }
+
+    fn request(messages: Vec<ChatMessage>) -> ChatRequest {
+        ChatRequest {
+            model: MODEL.to_string(),
+            messages,
+            options: Default::default(),
+            tools: Vec::new(),
+        }
+    }
+
+    // One provider of each wire format, both pointed at the same stub
+    async fn providers(turns: Vec<MockTurn>) -> (JoinHandle<()>, Vec<Box<dyn LlmProvider>>) {
+        let (task, port) = spawn_stub(MODEL, MockTranscript { turns }).await.unwrap();
+        let base_url = format!("http://127.0.0.1:{}", port);
+        let providers: Vec<Box<dyn LlmProvider>> = vec![
+            Box::new(OllamaProvider::new(ProviderEndpoint::new(&base_url))),
+            Box::new(OpenAICompatibleProvider::new(
+                "LM Studio",
+                ProviderEndpoint::new(&format!("{}/v1", base_url)),
+            )),
+        ];
+        (task, providers)
+    }
+
+    async fn stream(
+        provider: &dyn LlmProvider,
+        request: &ChatRequest,
+    ) -> (Vec<String>, Result<GenerationSummary, ProviderError>) {
+        let mut tokens = Vec::new();
+        let result = provider
+            .stream_chat(&Client::new(), request, &mut |token| {
+                if !token.is_empty() {
+                    tokens.push(token)
+                }
+            })
+            .await;
+        (tokens, result)
+    }
+
+    #[tokio::test]
+    async fn streams_tokens_and_usage() {
+        let (task, providers) = providers(vec![turn("Hello from the stub")]).await;
+
+        for provider in &providers {
+            let messages = vec![ChatMessage::new(Role::User, "hi")];
+            let (tokens, result) = stream(provider.as_ref(), &request(messages)).await;
+            let summary = result.unwrap();
+
+            assert_eq!(
+                tokens,
+                ["Hello ", "from ", "the ", "stub"],
+                "{}",
+                provider.name()
+            );
+            assert_eq!(summary.finish_reason.as_deref(), Some("stop"));
+            assert_eq!(summary.prompt_tokens, Some(12));
+            assert_eq!(summary.completion_tokens, Some(4));
+            assert!(summary.tool_calls.is_empty());
+        }
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn reports_errors_after_the_tokens_sent_so_far() {
+        let failing = MockTurn {
+            error: Some("model crashed".to_string()),
+            ..turn("partial answer")
+        };
+        let (task, providers) = providers(vec![failing]).await;
+
+        for provider in &providers {
+            let messages = vec![ChatMessage::new(Role::User, "hi")];
+            let (tokens, result) = stream(provider.as_ref(), &request(messages)).await;
+
+            assert_eq!(tokens, ["partial ", "answer"], "{}", provider.name());
+            let error = result.unwrap_err();
+            assert!(error.message.contains("model crashed"), "{}", error);
+        }
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn returns_tool_calls_then_replays_the_next_turn() {
+        let call = ToolCall {
+            id: "call_read".to_string(),
+            name: "read_file".to_string(),
+            arguments: serde_json::json!({ "path": "src/main.rs" }),
+        };
+        let asking = MockTurn {
+            tool_calls: vec![call.clone()],
+            ..Default::default()
+        };
+        let (task, providers) = providers(vec![asking, turn("It prints hello")]).await;
+
+        for provider in &providers {
+            let mut messages = vec![ChatMessage::new(Role::User, "what does main do?")];
+            let (tokens, result) = stream(provider.as_ref(), &request(messages.clone())).await;
+            let summary = result.unwrap();
+
+            assert!(tokens.is_empty(), "{}", provider.name());
+            assert_eq!(summary.finish_reason.as_deref(), Some("tool_calls"));
+            assert_eq!(summary.tool_calls.len(), 1);
+            assert_eq!(summary.tool_calls[0].name, "read_file");
+            assert_eq!(summary.tool_calls[0].arguments, call.arguments);
+
+            // With the call answered, the stub moves on to the second turn
+            let id = summary.tool_calls[0].id.clone();
+            messages.push(ChatMessage {
+                tool_calls: summary.tool_calls,
+                ..ChatMessage::new(Role::Assistant, "")
+            });
+            messages.push(ChatMessage {
+                tool_call_id: Some(id),
+                ..ChatMessage::new(Role::Tool, "fn main() { println!(\"hello\") }")
+            });
+            let (tokens, result) = stream(provider.as_ref(), &request(messages)).await;
+            assert_eq!(tokens.concat(), "It prints hello");
+            assert_eq!(result.unwrap().finish_reason.as_deref(), Some("stop"));
+        }
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn reports_the_stub_model_and_running_state() {
+        let (task, providers) = providers(vec![turn("unused")]).await;
+
+        for provider in &providers {
+            let client = Client::new();
+            provider.health_check(&client).await.unwrap();
+            let models = provider.list_models(&client).await.unwrap();
+            assert_eq!(models.len(), 1);
+            assert_eq!(models[0].name, MODEL);
+        }
+        let info = providers[0].backend_info(&Client::new()).await.unwrap();
+        assert_eq!(info.version.as_deref(), Some("0.0.0-stub"));
+        task.abort();
+    }
+}
@@ -2,7 +2,7 @@ import { useState, useEffect } from "react";
 import { Sparkles, Download, Check, Zap, Brain, Server, ArrowRight, Info } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { useAppStore } from "../../stores/appStore";
+import { useAppStore, type ProviderHealth } from "../../stores/appStore";
 
 type OnboardingStep = 'welcome' | 'instructions' | 'provider' | 'models';
 type Provider = 'ollama' | 'lmstudio';
@@ -45,8 +45,8 @@ export default function CinematicIntro() {
 
     const checkOllama = async () => {
         try {
-            const status = await invoke<boolean>("check_ollama_status");
-            setOllamaReady(status);
+            const report = await invoke<ProviderHealth[]>("check_providers", { providers: ["ollama"] });
+            setOllamaReady(report.some(p => p.id === 'ollama' && p.healthy));
         } catch {
             setOllamaReady(false);
         }
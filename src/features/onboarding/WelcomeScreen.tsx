@@ -1,6 +1,6 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { useAppStore } from "../../stores/appStore";
+import { useAppStore, type ProviderHealth } from "../../stores/appStore";
 import { Terminal, CheckCircle2, AlertCircle, ArrowRight, Loader2 } from "lucide-react";
 
 export default function WelcomeScreen() {
@@ -10,9 +10,10 @@ export default function WelcomeScreen() {
     const checkOllama = async () => {
         setOllamaStatus('checking');
         try {
-            // Mapping check_ollama_status to the command we registered
-            const isRunning = await invoke<boolean>("check_ollama_status");
-            setOllamaStatus(isRunning ? 'running' : 'not-running');
+            const report = await invoke<ProviderHealth[]>("check_providers", { providers: ["ollama"] });
+            const ollama = report.find(p => p.id === 'ollama');
+            if (ollama?.error) console.warn("Ollama unavailable:", ollama.error);
+            setOllamaStatus(ollama?.healthy ? 'running' : 'not-running');
         } catch (error) {
             console.error("Ollama check failed:", error);
             setOllamaStatus('not-running');
@@ -1,7 +1,10 @@
+import { invoke } from "@tauri-apps/api/core";
+
 interface CompletionRequest {
     code: string;
     cursorPosition: number;
     language: string;
+    path?: string; // Of the open file, shown to the model as a header comment
     context?: string[];
 }
 
@@ -25,145 +28,40 @@ class AICompletionService {
             return cached;
         }
 
-        try {
-            const startTime = performance.now();
-
-            // Call AI service
-            const response = await this.callAI(request);
-
-            const elapsed = performance.now() - startTime;
-            console.log(`⚡ Completion in ${elapsed.toFixed(0)}ms`);
-
-            // Cache result
-            this.cache.set(cacheKey, response);
-            setTimeout(() => this.cache.delete(cacheKey), this.CACHE_TTL);
-
-            return response;
-        } catch (error) {
-            console.error('Completion error:', error);
-            return null;
-        }
-    }
-
-    private async callAI(request: CompletionRequest): Promise<CompletionResponse> {
-        const context = this.buildContext(request);
-        const prompt = this.buildPrompt(request, context);
+        const startTime = performance.now();
 
+        let response: CompletionResponse;
         try {
-            // Try Ollama first (local, fast)
-            const response = await this.callOllama(prompt);
-            if (response) return response;
-
-            // Fallback to OpenAI
-            return await this.callOpenAI(prompt);
+            response = await this.callAI(request);
         } catch (error) {
-            console.error('AI call failed:', error);
+            // A newer keystroke already replaced this request
+            if (String(error).includes('superseded')) return null;
             throw error;
         }
-    }
-
-    private async callOllama(prompt: string): Promise<CompletionResponse | null> {
-        try {
-            const response = await fetch('http://localhost:11434/api/generate', {
-                method: 'POST',
-                headers: { 'Content-Type': 'application/json' },
-                body: JSON.stringify({
-                    model: 'codellama:7b',
-                    prompt,
-                    stream: true, // Enable streaming for faster first token
-                    options: {
-                        temperature: 0.2,
-                        top_p: 0.9,
-                        num_predict: 100,
-                        stop: ['\n\n', '```'] // Stop early for completions
-                    }
-                })
-            });
 
-            if (!response.ok) return null;
+        const elapsed = performance.now() - startTime;
+        console.log(`⚡ Completion in ${elapsed.toFixed(0)}ms`);
 
-            // Stream response for faster perceived performance
-            const reader = response.body?.getReader();
-            if (!reader) return null;
+        // Cache result
+        this.cache.set(cacheKey, response);
+        setTimeout(() => this.cache.delete(cacheKey), this.CACHE_TTL);
 
-            let fullResponse = '';
-            const decoder = new TextDecoder();
-
-            while (true) {
-                const { done, value } = await reader.read();
-                if (done) break;
-
-                const chunk = decoder.decode(value);
-                const lines = chunk.split('\n').filter(line => line.trim());
-
-                for (const line of lines) {
-                    try {
-                        const data = JSON.parse(line);
-                        if (data.response) {
-                            fullResponse += data.response;
-                        }
-                        if (data.done) break;
-                    } catch (e) {
-                        // Skip invalid JSON
-                    }
-                }
-            }
-
-            return {
-                suggestion: fullResponse.trim(),
-                confidence: 0.85
-            };
-        } catch {
-            return null;
-        }
+        return response;
     }
 
-    private async callOpenAI(prompt: string): Promise<CompletionResponse> {
-        // TODO: Integrate with your existing OpenAI service
-        const apiKey = localStorage.getItem('openai_api_key');
-
-        const response = await fetch('https://api.openai.com/v1/chat/completions', {
-            method: 'POST',
-            headers: {
-                'Content-Type': 'application/json',
-                'Authorization': `Bearer ${apiKey}`
-            },
-            body: JSON.stringify({
-                model: 'gpt-3.5-turbo',
-                messages: [{ role: 'user', content: prompt }],
-                max_tokens: 100,
-                temperature: 0.2,
-                stream: false
-            })
+    // The backend owns the model, API keys, timeout and superseding of older requests
+    private async callAI(request: CompletionRequest): Promise<CompletionResponse> {
+        const suggestion = await invoke<string>('complete_inline', {
+            prefix: request.code.slice(0, request.cursorPosition),
+            suffix: request.code.slice(request.cursorPosition),
+            path: request.path ?? null,
+            language: request.language,
         });
-
-        const data = await response.json();
-        return {
-            suggestion: data.choices[0].message.content.trim(),
-            confidence: 0.9
-        };
-    }
-
-    private buildContext(request: CompletionRequest): string {
-        // Build smart context from file
-        const lines = request.code.split('\n');
-        const imports = lines.filter(l => l.includes('import')).join('\n');
-        const recentCode = request.code.slice(-500); // Last 500 chars
-
-        return `${imports}\n\n${recentCode}`;
-    }
-
-    private buildPrompt(request: CompletionRequest, context: string): string {
-        return `Complete the following ${request.language} code. Only return the completion, no explanations.
-
-Context:
-${context}
-
-Complete from cursor position. Be concise and accurate.`;
+        return { suggestion, confidence: 0.85 };
     }
 
     private getCacheKey(request: CompletionRequest): string {
-        return `${request.language}:${request.code.slice(-100)}:${request.cursorPosition}`;
+        return `${request.path ?? ''}:${request.language}:${request.code.slice(-100)}:${request.cursorPosition}`;
     }
 
     debounce(fn: () => void): void {
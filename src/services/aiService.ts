@@ -1,106 +1,93 @@
-import { OpenAIProvider } from './providers/openai';
-import { AnthropicProvider } from './providers/anthropic';
-import { GeminiProvider } from './providers/gemini';
-import { OpenRouterProvider } from './providers/openrouter';
 import type { ChatCompletionRequest, ChatCompletionResponse } from '../types/ai';
 import { invoke } from '@tauri-apps/api/core';
-
+import { listen } from '@tauri-apps/api/event';
+
+// Display names used in the UI -> provider IDs in the Rust registry
+const PROVIDER_IDS: Record<string, string> = {
+    'Ollama': 'ollama',
+    'LM Studio': 'lmstudio',
+    'OpenAI': 'openai',
+    'Anthropic': 'anthropic',
+    'Google Gemini': 'gemini',
+    'OpenRouter': 'openrouter',
+};
+
+// Every provider runs in the Rust backend, which reads API keys from the
+// keychain itself, so keys never need to reach the webview.
 export class UnifiedAIService {
-    private openai: OpenAIProvider;
-    private anthropic: AnthropicProvider;
-    private gemini: GeminiProvider;
-    private openrouter: OpenRouterProvider;
-
-    constructor() {
-        this.openai = new OpenAIProvider();
-        this.anthropic = new AnthropicProvider();
-        this.gemini = new GeminiProvider();
-        this.openrouter = new OpenRouterProvider();
-    }
-
-    // Set API keys
-    setOpenAIKey(key: string) {
-        this.openai.setApiKey(key);
-    }
-
-    setAnthropicKey(key: string) {
-        this.anthropic.setApiKey(key);
-    }
-
-    setGeminiKey(key: string) {
-        this.gemini.setApiKey(key);
-    }
-
-    setOpenRouterKey(key: string) {
-        this.openrouter.setApiKey(key);
-    }
-
     // Unified chat method
     async chat(request: ChatCompletionRequest): Promise<ChatCompletionResponse> {
-        const provider = this.getProvider(request.provider);
-
-        // Handle local providers (Ollama/LM Studio)
-        if (request.provider === 'Ollama' || request.provider === 'LM Studio') {
-            return this.chatLocal(request);
+        let content = '';
+        for await (const token of this.chatStream(request)) {
+            content += token;
         }
-
-        // Handle cloud providers
-        return provider.chat(request);
+        return { content, usage: undefined, cost: 0 };
     }
 
     // Unified streaming method
     async *chatStream(request: ChatCompletionRequest): AsyncGenerator<string> {
-        const provider = this.getProvider(request.provider);
-
-        // Handle local providers
-        if (request.provider === 'Ollama' || request.provider === 'LM Studio') {
-            yield* this.chatStreamLocal(request);
-            return;
-        }
-
-        // Handle cloud providers
-        yield* provider.chatStream(request);
-    }
+        const provider = PROVIDER_IDS[request.provider] ?? request.provider;
+        const messages = [...request.messages];
+        const prompt = messages.pop()?.content ?? '';
+        const history = messages.filter(m => m.role !== 'system');
+
+        // Tokens arrive as events; queue them until the generator is pulled
+        const sessionId = crypto.randomUUID();
+        const queue: string[] = [];
+        let done = false;
+        let failure: string | null = null;
+        let wake: (() => void) | null = null;
+        const notify = () => { wake?.(); wake = null; };
+
+        const unlisteners = await Promise.all([
+            listen<{ session_id: string; token: string }>('generate-token', (event) => {
+                if (event.payload.session_id !== sessionId) return;
+                queue.push(event.payload.token);
+                notify();
+            }),
+            listen<{ session_id: string }>('generate-finished', (event) => {
+                if (event.payload.session_id !== sessionId) return;
+                done = true;
+                notify();
+            }),
+            listen<{ session_id: string; error: string }>('generate-error', (event) => {
+                if (event.payload.session_id !== sessionId) return;
+                failure = event.payload.error;
+                done = true;
+                notify();
+            }),
+            listen<{ session_id: string }>('generate-cancelled', (event) => {
+                if (event.payload.session_id !== sessionId) return;
+                done = true;
+                notify();
+            }),
+        ]);
 
-    // Local provider chat (Ollama/LM Studio)
-    private async chatLocal(request: ChatCompletionRequest): Promise<ChatCompletionResponse> {
         try {
-            const response = await invoke<string>('generate_code', {
-                prompt: request.messages[request.messages.length - 1].content,
+            await invoke<string>('generate_code', {
                 model: request.model,
+                prompt,
+                provider,
+                history,
+                sessionId,
+                options: {
+                    temperature: request.temperature ?? null,
+                    max_tokens: request.maxTokens ?? null,
+                },
             });
 
-            return {
-                content: response,
-                usage: undefined,
-                cost: 0,
-            };
-        } catch (error: any) {
-            throw new Error(`Local provider error: ${error}`);
-        }
-    }
-
-    // Local provider streaming
-    private async *chatStreamLocal(request: ChatCompletionRequest): AsyncGenerator<string> {
-        // For now, return non-streaming response
-        // TODO: Implement streaming for local providers
-        const response = await this.chatLocal(request);
-        yield response.content;
-    }
-
-    // Get provider instance
-    private getProvider(providerName: string): any {
-        switch (providerName) {
-            case 'OpenAI':
-                return this.openai;
-            case 'Anthropic':
-                return this.anthropic;
-            case 'Google Gemini':
-                return this.gemini;
-            case 'OpenRouter':
-                return this.openrouter;
-            default:
-                throw new Error(`Unknown provider: ${providerName}`);
+            while (true) {
+                while (queue.length > 0) {
+                    yield queue.shift()!;
+                }
+                if (done) break;
+                await new Promise<void>(resolve => { wake = resolve; });
+            }
+            if (failure) {
+                throw new Error(failure);
+            }
+        } finally {
+            unlisteners.forEach(unlisten => unlisten());
         }
     }
 }
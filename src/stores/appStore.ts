@@ -13,6 +13,121 @@ export interface APIKeys {
     openrouter?: string;
 }
 
+// Mirrors `ChatMessage` in the backend: plain text or a list of typed parts
+export type ChatRole = 'system' | 'user' | 'assistant' | 'tool';
+export type ContentPart =
+    | { type: 'text'; text: string }
+    | { type: 'image'; data: string; mime_type?: string };
+export interface ChatMessage {
+    role: ChatRole;
+    content: string | ContentPart[];
+}
+
+export interface GenerationSummary {
+    finish_reason: string | null;
+    prompt_tokens: number | null;
+    completion_tokens: number | null;
+    eval_duration_ms: number | null;
+    tokens_per_second: number | null;
+}
+
+// Where a generation actually ran, after retries and fallbacks
+export interface ProviderUsed {
+    provider: string;
+    model: string;
+    attempts: number;
+}
+
+// One row of the provider dashboard (`check_providers`)
+export interface LoadedModel {
+    name: string;
+    size: number | null;
+    size_vram: number | null;
+    processor: string | null; // "100% GPU", "48%/52% CPU/GPU", ...
+    context_length: number | null;
+    expires_at: string | null;
+}
+
+export interface ProviderHealth {
+    id: string;
+    name: string;
+    endpoint: string | null;
+    healthy: boolean;
+    latency_ms: number | null;
+    version: string | null;
+    models_available: number | null;
+    loaded_models: LoadedModel[];
+    error: string | null; // Why the provider is unhealthy
+    info_error: string | null; // Why version and models couldn't be read
+}
+
+export interface HistoryTrim {
+    context_length: number | null; // Unknown windows aren't trimmed to
+    estimated_prompt_tokens: number;
+    dropped_messages: number;
+    dropped_tokens: number;
+    summarized: boolean;
+}
+
+// `agent-tool-call` payload; approval_id is set for writes awaiting the user
+export interface AgentToolCall {
+    request_id: string;
+    session_id: string | null;
+    call_id: string;
+    tool: string;
+    arguments: unknown;
+    approval_id: string | null;
+}
+
+// Saved conversations (see sessions.rs); timestamps are unix millis
+export interface StoredMessage {
+    timestamp: number;
+    message: ChatMessage;
+    model: string | null;
+    provider: string | null;
+    usage: GenerationSummary | null;
+}
+
+export interface SessionInfo {
+    id: string;
+    title: string;
+    created_at: number;
+    updated_at: number;
+    message_count: number;
+    forked_from: string | null;
+}
+
+export interface Session {
+    info: SessionInfo;
+    messages: StoredMessage[];
+}
+
+// `search_conversations` result; the location fields match search_in_files
+export interface ConversationHit {
+    project: string;
+    session_id: string | null;
+    session_title: string;
+    message_index: number;
+    role: ChatRole | null;
+    timestamp: number | null;
+    in_code_block: boolean;
+    score: number;
+    file_path: string;
+    line_number: number;
+    line_content: string;
+    match_start: number;
+    match_end: number;
+}
+
+// `retrieve_context` result; lines are 1-based and inclusive
+export interface RetrievedChunk {
+    path: string;
+    start_line: number;
+    end_line: number;
+    content: string;
+    score: number;
+}
+
 interface AppStore {
     // State Machine
     appPhase: AppPhase;
@@ -29,6 +144,13 @@ interface AppStore {
     downloadStatus: string;
     generatedCode: string;
     currentResponse: string; // Current AI response being streamed
+    currentRequestId: string | null; // Backend ID of the in-flight generation
+    lastGenerationSummary: GenerationSummary | null; // Finish reason and token usage of the last completed generation
+    lastHistoryTrim: HistoryTrim | null; // History dropped to fit the model's context window
+    lastProviderUsed: ProviderUsed | null; // Differs from the selected provider after a fallback
+    pendingToolApproval: AgentToolCall | null; // Agent write waiting for the user's go-ahead
+    sessions: SessionInfo[]; // Saved conversations of the open project, most recent first
+    activeSession: Session | null; // The saved conversation being shown
 
     // Actions
     setPhase: (phase: AppPhase) => void;
@@ -42,7 +164,7 @@ interface AppStore {
     setAgentMode?: (mode: 'code' | 'architect' | 'normal') => void;
     currentPlan?: string;
     setAPIKey: (provider: keyof APIKeys, key: string) => Promise<void>;
-    getAPIKey: (provider: keyof APIKeys) => Promise<string | undefined>;
+    hasAPIKey: (provider: keyof APIKeys) => Promise<boolean>;
     migrateAPIKeys: () => Promise<void>;
     setDownloading: (isDownloading: boolean) => void;
     setProgress: (progress: number) => void;
@@ -50,8 +172,14 @@ interface AppStore {
     setCode: (code: string) => void;
     setCurrentResponse: (response: string) => void;
     // Streaming Actions
-    generateCode: (prompt: string, onResponse?: (response: string) => void) => Promise<void>;
+    generateCode: (prompt: string, onResponse?: (response: string) => void, images?: string[]) => Promise<void>;
     generateProject: (prompt: string, onResponse?: (response: string) => void) => Promise<string>;
+    cancelGeneration: () => Promise<void>;
+    runAgent: (prompt: string, projectPath: string, onResponse?: (response: string) => void) => Promise<string>;
+    approveToolCall: (approved: boolean) => Promise<void>;
+    // Saved conversations
+    loadSessions: (project: string) => Promise<void>;
+    openSession: (project: string, sessionId: string) => Promise<void>;
 }
 
 export const useAppStore = create<AppStore>()(
@@ -68,6 +196,13 @@ export const useAppStore = create<AppStore>()(
             downloadStatus: "",
             generatedCode: "",
             currentResponse: "",
+            currentRequestId: null,
+            lastGenerationSummary: null,
+            lastHistoryTrim: null,
+            lastProviderUsed: null,
+            pendingToolApproval: null,
+            sessions: [],
+            activeSession: null,
 
             // Actions
             setPhase: (phase) => set({ appPhase: phase }),
@@ -94,13 +229,12 @@ export const useAppStore = create<AppStore>()(
                 }
             },
 
-            getAPIKey: async (provider) => {
+            hasAPIKey: async (provider) => {
                 try {
-                    const key = await invoke<string>('get_api_key', { provider });
-                    return key || undefined;
+                    return await invoke<boolean>('has_api_key', { provider });
                 } catch (error) {
-                    console.error('Failed to get API key:', error);
-                    return undefined;
+                    console.error('Failed to check API key:', error);
+                    return false;
                 }
             },
 
@@ -138,7 +272,7 @@ export const useAppStore = create<AppStore>()(
             setCode: (code) => set({ generatedCode: code }),
             setCurrentResponse: (response) => set({ currentResponse: response }),
 
-            generateCode: async (prompt: string, onResponse?: (response: string) => void) => {
+            generateCode: async (prompt: string, onResponse?: (response: string) => void, images?: string[]) => {
                 const state = get();
                 if (!state.currentModel) return;
 
@@ -147,7 +281,12 @@ export const useAppStore = create<AppStore>()(
 
                 // Prepare Listener
                 let fullResponse = "";
+                // Only react to events from this generation; others may be streaming in parallel
+                const sessionId = crypto.randomUUID();
+                const isOurs = (event: any) => event.payload?.session_id === sessionId;
+
                 const unlistenToken = await listen('generate-token', (event: any) => {
+                    if (!isOurs(event)) return;
                     const payload = event.payload as { token: string };
                     fullResponse += payload.token;
 
@@ -171,22 +310,49 @@ export const useAppStore = create<AppStore>()(
                     }
                 });
 
-                const unlistenFinish = await listen('generate-finished', () => {
+                const unlistenFinish = await listen('generate-finished', (event: any) => {
+                    if (!isOurs(event)) return;
+                    set({
+                        lastGenerationSummary: event.payload.summary,
+                        lastHistoryTrim: event.payload.history,
+                        lastProviderUsed: {
+                            provider: event.payload.provider,
+                            model: event.payload.model,
+                            attempts: event.payload.attempts,
+                        },
+                    });
+                    cleanup();
+                });
+                const unlistenError = await listen('generate-error', (event: any) => {
+                    if (!isOurs(event)) return;
+                    console.error("Generation failed", event.payload.error);
+                    cleanup();
+                });
+                const unlistenCancelled = await listen('generate-cancelled', (event: any) => {
+                    if (isOurs(event)) cleanup();
+                });
+
+                const cleanup = () => {
                     unlistenToken();
                     unlistenFinish();
-                });
+                    unlistenError();
+                    unlistenCancelled();
+                    set({ currentRequestId: null });
+                };
 
                 try {
-                    await invoke('generate_code', {
+                    const requestId = await invoke<string>('generate_code', {
                         model: state.currentModel,
                         prompt: prompt,
                         provider: state.provider,
-                        history: ""
+                        history: [] as ChatMessage[],
+                        images: images ?? null,
+                        sessionId
                     });
+                    set({ currentRequestId: requestId });
                 } catch (e) {
                     console.error("Generation failed", e);
-                    unlistenToken();
-                    unlistenFinish();
+                    cleanup();
                     throw e;
                 }
             },
@@ -222,33 +388,186 @@ Generate ALL necessary files for a working project.`;
 
                 let fullResponse = "";
 
+                // Only react to events from this generation; others may be streaming in parallel
+                const sessionId = crypto.randomUUID();
+                const isOurs = (event: any) => event.payload?.session_id === sessionId;
+
                 const unlistenToken = await listen('generate-token', (event: any) => {
+                    if (!isOurs(event)) return;
                     const payload = event.payload as { token: string };
                     fullResponse += payload.token;
                     set({ currentResponse: fullResponse });
                     onResponse?.(fullResponse);
                 });
 
-                const unlistenFinish = await listen('generate-finished', () => {
+                let resolveDone!: (response: string) => void;
+                let rejectDone!: (error: Error) => void;
+                const done = new Promise<string>((resolve, reject) => {
+                    resolveDone = resolve;
+                    rejectDone = reject;
+                });
+
+                const unlistenFinish = await listen('generate-finished', (event: any) => {
+                    if (!isOurs(event)) return;
+                    set({
+                        lastGenerationSummary: event.payload.summary,
+                        lastHistoryTrim: event.payload.history,
+                        lastProviderUsed: {
+                            provider: event.payload.provider,
+                            model: event.payload.model,
+                            attempts: event.payload.attempts,
+                        },
+                    });
+                    cleanup();
+                    resolveDone(fullResponse);
+                });
+                const unlistenError = await listen('generate-error', (event: any) => {
+                    if (!isOurs(event)) return;
+                    cleanup();
+                    rejectDone(new Error(event.payload.error));
+                });
+                const unlistenCancelled = await listen('generate-cancelled', (event: any) => {
+                    if (!isOurs(event)) return;
+                    cleanup();
+                    resolveDone(fullResponse);
+                });
+
+                const cleanup = () => {
                     unlistenToken();
                     unlistenFinish();
-                });
+                    unlistenError();
+                    unlistenCancelled();
+                    set({ currentRequestId: null });
+                };
 
                 try {
-                    await invoke('generate_code', {
+                    const requestId = await invoke<string>('generate_code', {
                         model: state.currentModel,
                         prompt: projectPrompt,
                         provider: state.provider,
-                        history: ""
+                        history: [] as ChatMessage[],
+                        sessionId
                     });
-
-                    return fullResponse;
+                    set({ currentRequestId: requestId });
                 } catch (e) {
                     console.error("Project generation failed", e);
+                    cleanup();
+                    throw e;
+                }
+
+                return done;
+            },
+
+            cancelGeneration: async () => {
+                const requestId = get().currentRequestId;
+                if (!requestId) return;
+
+                await invoke('cancel_generation', { requestId });
+            },
+
+            // Agent mode: the backend reads and edits project files through tool calls
+            runAgent: async (prompt: string, projectPath: string, onResponse?: (response: string) => void): Promise<string> => {
+                const state = get();
+                if (!state.currentModel) throw new Error("No model selected");
+
+                let fullResponse = "";
+                const sessionId = crypto.randomUUID();
+                const isOurs = (event: any) => event.payload?.session_id === sessionId;
+
+                let resolveDone!: (response: string) => void;
+                let rejectDone!: (error: Error) => void;
+                const done = new Promise<string>((resolve, reject) => {
+                    resolveDone = resolve;
+                    rejectDone = reject;
+                });
+
+                const unlistenToken = await listen('generate-token', (event: any) => {
+                    if (!isOurs(event)) return;
+                    fullResponse += event.payload.token;
+                    set({ currentResponse: fullResponse });
+                    onResponse?.(fullResponse);
+                });
+                const unlistenToolCall = await listen('agent-tool-call', (event: any) => {
+                    if (!isOurs(event)) return;
+                    const call = event.payload as AgentToolCall;
+                    if (call.approval_id) set({ pendingToolApproval: call });
+                });
+                const unlistenToolResult = await listen('agent-tool-result', (event: any) => {
+                    if (!isOurs(event)) return;
+                    if (get().pendingToolApproval?.call_id === event.payload.call_id) {
+                        set({ pendingToolApproval: null });
+                    }
+                });
+                const unlistenFinish = await listen('generate-finished', (event: any) => {
+                    if (!isOurs(event)) return;
+                    set({
+                        lastGenerationSummary: event.payload.summary,
+                        lastHistoryTrim: event.payload.history,
+                        lastProviderUsed: {
+                            provider: event.payload.provider,
+                            model: event.payload.model,
+                            attempts: event.payload.attempts,
+                        },
+                    });
+                    cleanup();
+                    resolveDone(fullResponse);
+                });
+                const unlistenError = await listen('generate-error', (event: any) => {
+                    if (!isOurs(event)) return;
+                    cleanup();
+                    rejectDone(new Error(event.payload.error));
+                });
+                const unlistenCancelled = await listen('generate-cancelled', (event: any) => {
+                    if (!isOurs(event)) return;
+                    cleanup();
+                    resolveDone(fullResponse);
+                });
+
+                const cleanup = () => {
                     unlistenToken();
+                    unlistenToolCall();
+                    unlistenToolResult();
                     unlistenFinish();
+                    unlistenError();
+                    unlistenCancelled();
+                    set({ currentRequestId: null, pendingToolApproval: null });
+                };
+
+                try {
+                    const requestId = await invoke<string>('run_agent', {
+                        model: state.currentModel,
+                        prompt,
+                        provider: state.provider,
+                        projectPath,
+                        history: [] as ChatMessage[],
+                        sessionId
+                    });
+                    set({ currentRequestId: requestId });
+                } catch (e) {
+                    console.error("Agent run failed", e);
+                    cleanup();
                     throw e;
                 }
+
+                return done;
+            },
+
+            approveToolCall: async (approved: boolean) => {
+                const call = get().pendingToolApproval;
+                if (!call?.approval_id) return;
+
+                set({ pendingToolApproval: null });
+                await invoke('approve_tool_call', { approvalId: call.approval_id, approved });
+            },
+
+            loadSessions: async (project: string) => {
+                const sessions = await invoke<SessionInfo[]>('list_sessions', { project });
+                set({ sessions });
+            },
+
+            openSession: async (project: string, sessionId: string) => {
+                const session = await invoke<Session>('load_session', { project, sessionId });
+                set({ activeSession: session });
             },
         }),
         {
@@ -8,9 +8,12 @@ export interface FileNode {
     children?: FileNode[];
 }
 
+export type FileConflict = 'exists' | 'duplicate';
+
 export interface FileEntry {
     path: string;
     content: string;
+    conflict?: FileConflict;
 }
 
 export interface OpenFile {
@@ -49,9 +49,10 @@ export default function InlineCompletion({
 }
 
 // Monaco integration hook
-export function useAICompletion(editor: any, language: string) {
+export function useAICompletion(editor: any, language: string, path?: string) {
     const [suggestion, setSuggestion] = useState<string | null>(null);
     const [position, setPosition] = useState<any>(null);
+    const [error, setError] = useState<string | null>(null);
 
     useEffect(() => {
         if (!editor) return;
@@ -66,11 +67,22 @@ export function useAICompletion(editor: any, language: string) {
 
             // Debounced AI completion request
             completionService.debounce(async () => {
-                const result = await completionService.getCompletion({
-                    code,
-                    cursorPosition: cursorOffset,
-                    language
-                });
+                const result = await completionService
+                    .getCompletion({
+                        code,
+                        cursorPosition: cursorOffset,
+                        language,
+                        path
+                    })
+                    .then((res) => {
+                        setError(null);
+                        return res;
+                    })
+                    .catch((err) => {
+                        console.error('Inline completion failed:', err);
+                        setError(String(err));
+                        return null;
+                    });
 
                 if (result && result.confidence > 0.7) {
                     setSuggestion(result.suggestion);
@@ -82,7 +94,7 @@ export function useAICompletion(editor: any, language: string) {
         });
 
         return () => disposable.dispose();
-    }, [editor, language]);
+    }, [editor, language, path]);
 
     const acceptSuggestion = () => {
         if (!editor || !suggestion) return;
@@ -103,6 +115,7 @@ export function useAICompletion(editor: any, language: string) {
     return {
         suggestion,
         position,
+        error,
         acceptSuggestion,
         dismissSuggestion
     };
@@ -29,7 +29,8 @@ export default function MonacoWrapper({ initialValue, filePath, language = 'type
     // AI Completion integration
     const { suggestion, position, acceptSuggestion, dismissSuggestion } = useAICompletion(
         editorRef.current,
-        language
+        language,
+        filePath
     );
 
     // Configure TypeScript language features
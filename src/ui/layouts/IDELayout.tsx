@@ -72,38 +72,6 @@ export default function IDELayout() {
         migrateKeys();
     }, []);
 
-    // Initialize AI service with API keys
-    useEffect(() => {
-        const initializeAIService = async () => {
-            const { getAPIKey } = useAppStore.getState();
-
-            try {
-                const openaiKey = await getAPIKey('openai');
-                const anthropicKey = await getAPIKey('anthropic');
-                const geminiKey = await getAPIKey('gemini');
-                const openrouterKey = await getAPIKey('openrouter');
-
-                if (openaiKey) aiService.setOpenAIKey(openaiKey);
-                if (anthropicKey) aiService.setAnthropicKey(anthropicKey);
-                if (geminiKey) aiService.setGeminiKey(geminiKey);
-                if (openrouterKey) aiService.setOpenRouterKey(openrouterKey);
-            } catch (error) {
-                console.error('Failed to initialize AI service:', error);
-            }
-        };
-
-        initializeAIService();
-    }, []);
-
-    // Initialize API keys in aiService
-    useEffect(() => {
-        const { apiKeys } = useAppStore.getState();
-        if (apiKeys.openai) aiService.setOpenAIKey(apiKeys.openai);
-        if (apiKeys.anthropic) aiService.setAnthropicKey(apiKeys.anthropic);
-        if (apiKeys.gemini) aiService.setGeminiKey(apiKeys.gemini);
-        if (apiKeys.openrouter) aiService.setOpenRouterKey(apiKeys.openrouter);
-    }, []);
-
     // Update selected model when currentModel changes
     useEffect(() => {
         if (currentModel) {
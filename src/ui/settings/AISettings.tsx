@@ -2,30 +2,14 @@ import { Settings as SettingsIcon, Key, Cloud } from 'lucide-react';
 import { useAppStore } from '../../stores/appStore';
 import { AI_PROVIDERS } from '../../types/ai';
 import { useState } from 'react';
-import { aiService } from '../../services/aiService';
 
 export default function AISettings() {
     const { apiKeys, setAPIKey, provider, setProvider, currentModel, setCurrentModel } = useAppStore();
     const [showKeys, setShowKeys] = useState(false);
 
     const handleAPIKeyChange = (providerKey: 'openai' | 'anthropic' | 'gemini' | 'openrouter', value: string) => {
+        // Stored in the OS keychain; the Rust providers read it from there
         setAPIKey(providerKey, value);
-
-        // Update aiService with new key
-        switch (providerKey) {
-            case 'openai':
-                aiService.setOpenAIKey(value);
-                break;
-            case 'anthropic':
-                aiService.setAnthropicKey(value);
-                break;
-            case 'gemini':
-                aiService.setGeminiKey(value);
-                break;
-            case 'openrouter':
-                aiService.setOpenRouterKey(value);
-                break;
-        }
     };
 
     const cloudProviders = AI_PROVIDERS.filter(p => p.type === 'cloud');